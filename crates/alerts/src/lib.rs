@@ -57,8 +57,8 @@ impl AlertManager {
             last_triggered: None,
         };
 
-        self.alerts.insert(alert.config.id.clone(), alert);
         info!("Added alert: {}", alert.config.id);
+        self.alerts.insert(alert.config.id.clone(), alert);
         Ok(())
     }
 
@@ -95,16 +95,16 @@ impl AlertManager {
                 if should_trigger && !alert.triggered {
                     alert.triggered = true;
                     alert.last_triggered = Some(chrono::Utc::now().timestamp_millis());
-                    self.trigger_alert(alert);
+                    Self::trigger_alert(alert);
                 } else if !should_trigger && alert.triggered {
                     alert.triggered = false;
-                    self.resolve_alert(alert);
+                    Self::resolve_alert(alert);
                 }
             }
         }
     }
 
-    fn trigger_alert(&self, alert: &Alert) {
+    fn trigger_alert(alert: &Alert) {
         warn!(
             "ALERT TRIGGERED: {} - Metric '{}' {} {}",
             alert.config.name,
@@ -119,7 +119,7 @@ impl AlertManager {
         // In a full implementation, this would send notifications via email, Slack, etc.
     }
 
-    fn resolve_alert(&self, alert: &Alert) {
+    fn resolve_alert(alert: &Alert) {
         info!("ALERT RESOLVED: {}", alert.config.name);
         // In a full implementation, this would send resolution notifications
     }
@@ -230,13 +230,7 @@ mod tests {
             load15: 0.0,
             network: vec![],
             disks: vec![],
-            top_processes: vec![],
-            sensors: vec![],
-            batteries: vec![],
-            gpus: vec![],
-            connections: vec![],
-            process_tree: vec![],
-            containers: vec![],
+            ..Default::default()
         }
     }
 }
\ No newline at end of file
@@ -3,18 +3,20 @@ use core_metrics::collectors::connections::collect_connections;
 fn main() {
     println!("Testing connection tracking...");
     
-    let connections = collect_connections();
+    let connections = collect_connections(&Default::default(), &mut Default::default()).expect("reading socket tables");
     
     println!("Found {} connections", connections.len());
     
     for (i, conn) in connections.iter().enumerate() {
         println!(
-            "{}. Protocol: {}, Local: {}, Remote: {}, State: {:?}",
+            "{}. Protocol: {}, Local: {}, Remote: {}, State: {:?}, PID: {:?}, Process: {:?}",
             i + 1,
             conn.protocol,
             conn.local_address,
             conn.remote_address,
-            conn.state
+            conn.state,
+            conn.pid,
+            conn.process_name
         );
         
         // Limit output for readability
@@ -22,21 +22,14 @@ fn main() {
     let components = Components::new_with_refreshed_list();
     let sensors: Vec<SensorInfo> = components
         .iter()
-        .map(|component| {
-            SensorInfo {
-                component: component.label().to_string(),
-                temperature: component.temperature(),
-                unit: "°C".to_string(),
-                label: component.label().to_string(),
-            }
-        })
+        .map(SensorInfo::from_component)
         .collect();
     
     if sensors.is_empty() {
         println!("  No sensors detected");
     } else {
         for sensor in &sensors {
-            println!("  {} - {}{}", sensor.label, sensor.temperature, sensor.unit);
+            println!("  {} - {}{}", sensor.label, sensor.value, sensor.unit.symbol());
         }
     }
     
@@ -71,8 +64,9 @@ fn main() {
     let snapshot = Snapshot::from_sysinfo(
         chrono::Utc::now(),
         &sys,
-        sysinfo::System::load_average(),
-        None
+        None,
+        &Default::default(),
+        &Default::default()
     );
     
     println!("  Snapshot created with:");
@@ -44,13 +44,7 @@ fn main() {
         load15: 0.0,
         network: initial_networks,
         disks: vec![],
-        top_processes: vec![],
-        sensors: vec![],
-        batteries: vec![],
-        gpus: vec![],
-        connections: vec![],
-        process_tree: vec![],
-        containers: vec![],
+        ..Default::default()
     };
     
     // Calculate network data with throughput
@@ -0,0 +1,116 @@
+use crate::collectors::snapshot::Snapshot;
+use std::sync::Arc;
+use tokio::sync::{broadcast, watch};
+
+// Snapshots a subscriber may fall behind by before it starts missing them
+pub const SUBSCRIBER_BUFFER: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum RecvError {
+    #[error("subscriber fell behind and missed {0} snapshots")]
+    Lagged(u64),
+    #[error("supervisor stopped")]
+    Closed,
+}
+
+/// Fans every snapshot out to all subscribers and keeps the latest one at hand.
+#[derive(Clone)]
+pub struct SnapshotBus {
+    tx: broadcast::Sender<Arc<Snapshot>>,
+    latest: watch::Sender<Option<Arc<Snapshot>>>,
+}
+
+impl SnapshotBus {
+    pub fn new(capacity: usize) -> Self {
+        let (tx, _) = broadcast::channel(capacity);
+        let (latest, _) = watch::channel(None);
+        Self { tx, latest }
+    }
+
+    pub fn publish(&self, snapshot: Arc<Snapshot>) {
+        self.latest.send_replace(Some(snapshot.clone()));
+        // Err only means nobody is subscribed right now
+        let _ = self.tx.send(snapshot);
+    }
+
+    pub fn subscribe(&self) -> SnapshotReceiver {
+        SnapshotReceiver { rx: self.tx.subscribe() }
+    }
+
+    // Doesn't consume anything, so any number of readers can poll it
+    pub fn latest(&self) -> Option<Arc<Snapshot>> {
+        self.latest.borrow().clone()
+    }
+
+    // Wakes on every new snapshot but only ever holds the newest
+    pub fn watch(&self) -> watch::Receiver<Option<Arc<Snapshot>>> {
+        self.latest.subscribe()
+    }
+}
+
+/// One subscriber's view of the bus; sees every snapshot published after it subscribed.
+pub struct SnapshotReceiver {
+    rx: broadcast::Receiver<Arc<Snapshot>>,
+}
+
+impl SnapshotReceiver {
+    pub async fn recv(&mut self) -> Result<Arc<Snapshot>, RecvError> {
+        self.rx.recv().await.map_err(|e| match e {
+            broadcast::error::RecvError::Lagged(n) => RecvError::Lagged(n),
+            broadcast::error::RecvError::Closed => RecvError::Closed,
+        })
+    }
+
+    // Ok(None) when nothing new has arrived yet
+    pub fn try_recv(&mut self) -> Result<Option<Arc<Snapshot>>, RecvError> {
+        match self.rx.try_recv() {
+            Ok(snapshot) => Ok(Some(snapshot)),
+            Err(broadcast::error::TryRecvError::Empty) => Ok(None),
+            Err(broadcast::error::TryRecvError::Lagged(n)) => Err(RecvError::Lagged(n)),
+            Err(broadcast::error::TryRecvError::Closed) => Err(RecvError::Closed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(ts: i64) -> Arc<Snapshot> {
+        Arc::new(Snapshot { ts, ..Default::default() })
+    }
+
+    #[test]
+    fn test_every_subscriber_gets_every_snapshot() {
+        let bus = SnapshotBus::new(4);
+        let mut web = bus.subscribe();
+        let mut exporter = bus.subscribe();
+        assert!(bus.latest().is_none());
+
+        bus.publish(snapshot(1));
+        bus.publish(snapshot(2));
+
+        for rx in [&mut web, &mut exporter] {
+            assert_eq!(rx.try_recv().unwrap().unwrap().ts, 1);
+            assert_eq!(rx.try_recv().unwrap().unwrap().ts, 2);
+            assert!(rx.try_recv().unwrap().is_none());
+        }
+        // Reading the latest snapshot takes nothing away from subscribers
+        assert_eq!(bus.latest().unwrap().ts, 2);
+        assert_eq!(bus.latest().unwrap().ts, 2);
+    }
+
+    #[test]
+    fn test_stalled_subscriber_is_told_it_lagged() {
+        let bus = SnapshotBus::new(2);
+        let mut rx = bus.subscribe();
+        for ts in 1..=5 {
+            bus.publish(snapshot(ts));
+        }
+
+        assert_eq!(rx.try_recv().unwrap_err(), RecvError::Lagged(3));
+        // Then carries on from the oldest snapshot still buffered
+        assert_eq!(rx.try_recv().unwrap().unwrap().ts, 4);
+        assert_eq!(rx.try_recv().unwrap().unwrap().ts, 5);
+    }
+}
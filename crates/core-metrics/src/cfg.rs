@@ -1,7 +1,10 @@
-use serde::Deserialize; use std::fs;
-#[derive(Debug, Clone, Deserialize)] pub struct Config{pub refresh:Refresh,pub collectors:Collectors,pub exporters:Exporters,pub web:Web}
+use serde::Deserialize; use std::{fs, collections::HashMap}; use crate::collectors::{process::ProcessOptions, disk::FilesystemOptions, fsroot::FsRoot};
+#[derive(Debug, Clone, Deserialize)] pub struct Config{pub refresh:Refresh,pub collectors:Collectors,#[serde(default)] pub processes:ProcessOptions,#[serde(default)] pub filesystems:FilesystemOptions,#[serde(default)] pub paths:FsRoot,#[serde(default)] pub history:History,pub exporters:Exporters,pub web:Web}
 #[derive(Debug, Clone, Deserialize)] pub struct Refresh{pub interval_ms:u64}
-#[derive(Debug, Clone, Deserialize, Default)] pub struct Collectors{pub cpu:bool,pub mem:bool,pub load:bool}
+#[derive(Debug, Clone, Deserialize)] pub struct Collectors{pub cpu:bool,pub mem:bool,pub load:bool,#[serde(default)] pub disabled:Vec<String>,#[serde(default)] pub intervals_ms:HashMap<String,u64>}
+impl Default for Collectors{fn default()->Self{Self{cpu:true,mem:true,load:true,disabled:Vec::new(),intervals_ms:HashMap::new()}}}
+#[derive(Debug, Clone, Deserialize)] #[serde(default)] pub struct History{pub raw_minutes:u64,pub rollup_10s_hours:u64,pub rollup_1m_days:u64}
+impl Default for History{fn default()->Self{Self{raw_minutes:10,rollup_10s_hours:24,rollup_1m_days:7}}}
 #[derive(Debug, Clone, Deserialize, Default)] pub struct Exporters{#[serde(default)] pub prometheus: Option<PromConfig>, #[serde(skip)] pub prometheus_bind: Option<String>}
 #[derive(Debug, Clone, Deserialize)] pub struct PromConfig{pub bind:String}
 #[derive(Debug, Clone, Deserialize, Default)] pub struct Web{pub bind: Option<String>}
@@ -0,0 +1,613 @@
+use std::collections::HashMap;
+use std::time::Duration;
+use sysinfo::{CpuRefreshKind, MemoryRefreshKind, ProcessRefreshKind, RefreshKind};
+use super::registry::{Collector, CollectContext};
+use super::snapshot::Snapshot;
+use super::{net::NetworkInfo, disk::{DiskIoInfo, FilesystemOptions, HungMounts}, process::{ProcessInfo, ProcessOptions, ProcessRow, ProcessSortKey}, sensors::SensorInfo, process_tree::ProcessTreeNode, system::{DetectedVirtualization, SystemInfo}, storage_health::SmartMonitor, connections::SocketOwnerCache};
+use crate::cfg::Collectors;
+
+/// The built-in collectors, in the order they have to run.
+pub fn builtin_collectors(
+    process_options: ProcessOptions,
+    filesystem_options: FilesystemOptions,
+    smart: Option<SmartMonitor>,
+) -> Vec<Box<dyn Collector>> {
+    vec![
+        Box::new(SystemCollector { detected: DetectedVirtualization::default() }),
+        Box::new(CpuCollector),
+        Box::new(InterruptCollector),
+        Box::new(TopologyCollector),
+        Box::new(MemoryCollector),
+        Box::new(VmstatCollector),
+        Box::new(LoadCollector),
+        Box::new(PressureCollector),
+        Box::new(NetworkCollector { networks: sysinfo::Networks::new(), listed_at: None }),
+        Box::new(TcpCollector),
+        Box::new(FilesystemCollector { options: filesystem_options, disks: sysinfo::Disks::new(), listed_at: None, smart, hung: HungMounts::default() }),
+        Box::new(DiskIoCollector),
+        Box::new(StorageCollector),
+        Box::new(SensorCollector { components: None }),
+        Box::new(PowerSupplyCollector),
+        Box::new(RaplCollector),
+        Box::new(ProcessCollector { options: process_options }),
+        Box::new(ProcessTreeCollector),
+        Box::new(ConnectionCollector { owners: SocketOwnerCache::default() }),
+        Box::new(ContainerCollector),
+    ]
+}
+
+// Host header data
+struct SystemCollector {
+    detected: DetectedVirtualization,
+}
+
+impl Collector for SystemCollector {
+    fn name(&self) -> &'static str {
+        "system"
+    }
+
+    // Only needs the CPU list, for the model name
+    fn refresh_kind(&self) -> RefreshKind {
+        RefreshKind::new().with_cpu(CpuRefreshKind::new())
+    }
+
+    fn collect(&mut self, ctx: &CollectContext, snapshot: &mut Snapshot) -> anyhow::Result<()> {
+        snapshot.system = SystemInfo::collect(ctx.sys, ctx.root, &mut self.detected)?;
+        Ok(())
+    }
+}
+
+// Per-core usage and the /proc/stat time breakdown
+struct CpuCollector;
+
+impl Collector for CpuCollector {
+    fn name(&self) -> &'static str {
+        "cpu"
+    }
+
+    fn enabled(&self, toggles: &Collectors) -> bool {
+        toggles.cpu
+    }
+
+    fn refresh_kind(&self) -> RefreshKind {
+        RefreshKind::new().with_cpu(CpuRefreshKind::new().with_cpu_usage())
+    }
+
+    fn collect(&mut self, ctx: &CollectContext, snapshot: &mut Snapshot) -> anyhow::Result<()> {
+        let cpus = ctx.sys.cpus();
+        snapshot.cpu_per_core = cpus.iter().map(|c| c.cpu_usage()).collect();
+
+        // Compare the CPU time breakdown with the previous snapshot
+        snapshot.cpu_stats = super::cpu_mem::collect_cpu_stats(ctx.root)?.map(|stats| {
+            match ctx.previous.and_then(|prev| prev.cpu_stats.as_ref()) {
+                Some(prev_stats) => stats.with_breakdown(prev_stats),
+                None => stats,
+            }
+        });
+
+        // Prefer the /proc/stat figure, which covers exactly the interval between snapshots
+        snapshot.cpu_total_pct = match snapshot.cpu_stats.as_ref().and_then(|s| s.total.breakdown.as_ref()) {
+            Some(breakdown) => breakdown.busy(),
+            None if cpus.is_empty() => 0.0,
+            None => cpus.iter().map(|c| c.cpu_usage() as f64).sum::<f64>() / cpus.len() as f64,
+        };
+        Ok(())
+    }
+}
+
+// Interrupt and softirq rates; runs after the CPU collector to flag saturated CPUs
+struct InterruptCollector;
+
+impl Collector for InterruptCollector {
+    fn name(&self) -> &'static str {
+        "interrupts"
+    }
+
+    fn collect(&mut self, ctx: &CollectContext, snapshot: &mut Snapshot) -> anyhow::Result<()> {
+        snapshot.interrupts = super::interrupts::collect_interrupts(ctx.root)?.map(|stats| {
+            let mut stats = match ctx.rate_base(|prev| prev.interrupts.as_ref()) {
+                Some((prev_irq, elapsed_ms)) => stats.with_rates(prev_irq, elapsed_ms),
+                None => stats,
+            };
+            stats.flag_net_saturation(snapshot.cpu_stats.as_ref());
+            stats
+        });
+        Ok(())
+    }
+}
+
+// NUMA nodes and socket/core placement of each logical CPU
+struct TopologyCollector;
+
+impl Collector for TopologyCollector {
+    fn name(&self) -> &'static str {
+        "topology"
+    }
+
+    fn collect(&mut self, ctx: &CollectContext, snapshot: &mut Snapshot) -> anyhow::Result<()> {
+        snapshot.topology = super::numa_topology::collect_numa_topology(ctx.root)?;
+        Ok(())
+    }
+}
+
+// Memory and swap totals plus the meminfo breakdown
+struct MemoryCollector;
+
+impl Collector for MemoryCollector {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    fn enabled(&self, toggles: &Collectors) -> bool {
+        toggles.mem
+    }
+
+    fn refresh_kind(&self) -> RefreshKind {
+        RefreshKind::new().with_memory(MemoryRefreshKind::everything())
+    }
+
+    fn collect(&mut self, ctx: &CollectContext, snapshot: &mut Snapshot) -> anyhow::Result<()> {
+        snapshot.memory = super::vmstat::collect_memory_stats(ctx.root)?;
+
+        // meminfo from the configured root, which may not be this host's; sysinfo elsewhere
+        match snapshot.memory.as_ref() {
+            Some(mem) => {
+                snapshot.mem_total_bytes = mem.total;
+                snapshot.mem_used_bytes = mem.total.saturating_sub(mem.available);
+                snapshot.swap_total_bytes = mem.swap_total;
+                snapshot.swap_used_bytes = mem.swap_total.saturating_sub(mem.swap_free);
+            }
+            // sysinfo reports bytes already
+            None => {
+                let sys = ctx.sys;
+                snapshot.mem_total_bytes = sys.total_memory();
+                snapshot.mem_used_bytes = sys.total_memory().saturating_sub(sys.available_memory());
+                snapshot.swap_total_bytes = sys.total_swap();
+                snapshot.swap_used_bytes = sys.total_swap().saturating_sub(sys.free_swap());
+            }
+        }
+        Ok(())
+    }
+}
+
+// Paging and reclaim counters, as rates
+struct VmstatCollector;
+
+impl Collector for VmstatCollector {
+    fn name(&self) -> &'static str {
+        "vmstat"
+    }
+
+    fn enabled(&self, toggles: &Collectors) -> bool {
+        toggles.mem
+    }
+
+    fn collect(&mut self, ctx: &CollectContext, snapshot: &mut Snapshot) -> anyhow::Result<()> {
+        snapshot.vmstat = super::vmstat::collect_vm_stats(ctx.root)?.map(|stats| {
+            match ctx.rate_base(|prev| prev.vmstat.as_ref()) {
+                Some((prev_vm, elapsed_ms)) => stats.with_rates(prev_vm, elapsed_ms),
+                None => stats,
+            }
+        });
+        Ok(())
+    }
+}
+
+struct LoadCollector;
+
+impl Collector for LoadCollector {
+    fn name(&self) -> &'static str {
+        "load"
+    }
+
+    fn enabled(&self, toggles: &Collectors) -> bool {
+        toggles.load
+    }
+
+    fn collect(&mut self, _ctx: &CollectContext, snapshot: &mut Snapshot) -> anyhow::Result<()> {
+        let load_avg = sysinfo::System::load_average();
+        snapshot.load1 = load_avg.one;
+        snapshot.load5 = load_avg.five;
+        snapshot.load15 = load_avg.fifteen;
+        Ok(())
+    }
+}
+
+// Pressure stall information
+struct PressureCollector;
+
+impl Collector for PressureCollector {
+    fn name(&self) -> &'static str {
+        "pressure"
+    }
+
+    fn collect(&mut self, ctx: &CollectContext, snapshot: &mut Snapshot) -> anyhow::Result<()> {
+        snapshot.pressure = super::psi::collect_pressure(ctx.root)?;
+        Ok(())
+    }
+}
+
+// Re-listing picks up interfaces that came and went (container veths); in between only
+// the counters of known interfaces are refreshed
+const NETWORK_LIST_INTERVAL_MS: i64 = 10_000;
+
+// Interface counters, link metadata and throughput
+struct NetworkCollector {
+    networks: sysinfo::Networks,
+    listed_at: Option<i64>,
+}
+
+impl Collector for NetworkCollector {
+    fn name(&self) -> &'static str {
+        "network"
+    }
+
+    fn collect(&mut self, ctx: &CollectContext, snapshot: &mut Snapshot) -> anyhow::Result<()> {
+        let now_ms = ctx.now.timestamp_millis();
+        if matches!(self.listed_at, Some(t) if now_ms - t < NETWORK_LIST_INTERVAL_MS) {
+            self.networks.refresh();
+        } else {
+            self.networks.refresh_list();
+            self.listed_at = Some(now_ms);
+        }
+        let mut network: Vec<NetworkInfo> = self.networks
+            .iter()
+            .map(|(name, data)| NetworkInfo::from_network_data(name, data))
+            .collect();
+
+        // Link metadata first, so throughput can be compared with link speed
+        super::net::attach_interface_details(ctx.root, &mut network);
+
+        if let Some((prev_network, elapsed_ms)) = ctx.rate_base(|prev| Some(&prev.network)) {
+            let prev_network_map: HashMap<&str, &NetworkInfo> = prev_network
+                .iter()
+                .map(|net| (net.interface.as_str(), net))
+                .collect();
+            network = network
+                .into_iter()
+                .map(|net| match prev_network_map.get(net.interface.as_str()) {
+                    Some(prev_net) => net.with_throughput(prev_net, elapsed_ms),
+                    None => net,
+                })
+                .collect();
+        }
+
+        snapshot.network = network;
+        Ok(())
+    }
+}
+
+// TCP stack counters, as rates
+struct TcpCollector;
+
+impl Collector for TcpCollector {
+    fn name(&self) -> &'static str {
+        "tcp"
+    }
+
+    fn collect(&mut self, ctx: &CollectContext, snapshot: &mut Snapshot) -> anyhow::Result<()> {
+        snapshot.tcp = super::tcp::collect_tcp_stats(ctx.root)?.map(|stats| {
+            match ctx.rate_base(|prev| prev.tcp.as_ref()) {
+                Some((prev_tcp, elapsed_ms)) => stats.with_rates(prev_tcp, elapsed_ms),
+                None => stats,
+            }
+        });
+        Ok(())
+    }
+}
+
+// Mounts come and go, but re-listing them means a statfs pass over every mount, so in
+// between only the usage of known mounts is refreshed. Linux reads the mount table itself
+const DISK_LIST_INTERVAL_MS: i64 = 10_000;
+
+// Mounted filesystems minus the configured pseudo filesystems, with SMART health attached
+struct FilesystemCollector {
+    options: FilesystemOptions,
+    disks: sysinfo::Disks,
+    listed_at: Option<i64>,
+    // None where smartctl would see other drives than the ones being reported on
+    smart: Option<SmartMonitor>,
+    hung: HungMounts,
+}
+
+impl Collector for FilesystemCollector {
+    fn name(&self) -> &'static str {
+        "filesystems"
+    }
+
+    fn collect(&mut self, ctx: &CollectContext, snapshot: &mut Snapshot) -> anyhow::Result<()> {
+        if !cfg!(target_os = "linux") {
+            let now_ms = ctx.now.timestamp_millis();
+            if matches!(self.listed_at, Some(t) if now_ms - t < DISK_LIST_INTERVAL_MS) {
+                self.disks.refresh();
+            } else {
+                self.disks.refresh_list();
+                self.listed_at = Some(now_ms);
+            }
+        }
+        snapshot.disks = super::disk::collect_filesystems(ctx.root, &self.options, &self.hung, &self.disks)?;
+        let health = self.smart.as_mut().map(|smart| smart.poll(std::time::Instant::now())).unwrap_or_default();
+        super::storage_health::attach_disk_health(ctx.root, &mut snapshot.disks, &health);
+        Ok(())
+    }
+}
+
+// Per-device I/O rates; runs after the filesystem collector to attach mount points
+struct DiskIoCollector;
+
+impl Collector for DiskIoCollector {
+    fn name(&self) -> &'static str {
+        "disk_io"
+    }
+
+    fn collect(&mut self, ctx: &CollectContext, snapshot: &mut Snapshot) -> anyhow::Result<()> {
+        let mut disk_io = super::disk::collect_disk_io(ctx.root)?;
+        if let Some((prev_disk_io, elapsed_ms)) = ctx.rate_base(|prev| Some(&prev.disk_io)) {
+            let prev_disk_io_map: HashMap<&str, &DiskIoInfo> = prev_disk_io
+                .iter()
+                .map(|io| (io.device.as_str(), io))
+                .collect();
+            disk_io = disk_io
+                .into_iter()
+                .map(|io| match prev_disk_io_map.get(io.device.as_str()) {
+                    Some(prev_io) => io.with_rates(prev_io, elapsed_ms),
+                    None => io,
+                })
+                .collect();
+        }
+        super::disk::attach_mount_points(&mut disk_io, &snapshot.disks);
+        snapshot.disk_io = disk_io;
+        Ok(())
+    }
+}
+
+// RAID, ZFS and device-mapper state
+struct StorageCollector;
+
+impl Collector for StorageCollector {
+    fn name(&self) -> &'static str {
+        "storage"
+    }
+
+    fn collect(&mut self, ctx: &CollectContext, snapshot: &mut Snapshot) -> anyhow::Result<()> {
+        let storage = super::storage_health::collect_storage_health(ctx.root)?;
+        snapshot.storage = match ctx.previous {
+            Some(prev) => storage.with_rates(&prev.storage),
+            None => storage,
+        };
+        Ok(())
+    }
+}
+
+// hwmon sensors, falling back to sysinfo's temperature-only view
+struct SensorCollector {
+    components: Option<sysinfo::Components>,   // Only created once the fallback is needed
+}
+
+impl Collector for SensorCollector {
+    fn name(&self) -> &'static str {
+        "sensors"
+    }
+
+    fn collect(&mut self, ctx: &CollectContext, snapshot: &mut Snapshot) -> anyhow::Result<()> {
+        let mut sensors: Vec<SensorInfo> = super::sensors::collect_hwmon_sensors(ctx.root)?;
+        if sensors.is_empty() {
+            let components = match self.components.as_mut() {
+                Some(components) => {
+                    components.refresh();
+                    components
+                }
+                None => self.components.insert(sysinfo::Components::new_with_refreshed_list()),
+            };
+            sensors = components.iter().map(SensorInfo::from_component).collect();
+        }
+        snapshot.sensors = sensors;
+        Ok(())
+    }
+}
+
+// Batteries and AC adapter from the power_supply class
+struct PowerSupplyCollector;
+
+impl Collector for PowerSupplyCollector {
+    fn name(&self) -> &'static str {
+        "power_supply"
+    }
+
+    fn collect(&mut self, ctx: &CollectContext, snapshot: &mut Snapshot) -> anyhow::Result<()> {
+        let power_supplies = super::sensors::collect_power_supplies(ctx.root)?;
+        snapshot.batteries = power_supplies.batteries;
+        snapshot.ac_online = power_supplies.ac_online;
+        Ok(())
+    }
+}
+
+// RAPL energy counters, as watts
+struct RaplCollector;
+
+impl Collector for RaplCollector {
+    fn name(&self) -> &'static str {
+        "rapl"
+    }
+
+    fn collect(&mut self, ctx: &CollectContext, snapshot: &mut Snapshot) -> anyhow::Result<()> {
+        let rapl = super::rapl::collect_rapl(ctx.root)?;
+        snapshot.rapl = match ctx.rate_base(|prev| Some(&prev.rapl)) {
+            Some((prev_rapl, elapsed_ms)) => rapl.with_power(prev_rapl, elapsed_ms),
+            None => rapl,
+        };
+        Ok(())
+    }
+}
+
+// Top N processes by the configured key, or every process in all-processes mode
+struct ProcessCollector {
+    options: ProcessOptions,
+}
+
+impl Collector for ProcessCollector {
+    fn name(&self) -> &'static str {
+        "processes"
+    }
+
+    fn refresh_kind(&self) -> RefreshKind {
+        RefreshKind::new().with_processes(ProcessRefreshKind::new().with_cpu().with_memory().with_disk_usage())
+    }
+
+    fn collect(&mut self, ctx: &CollectContext, snapshot: &mut Snapshot) -> anyhow::Result<()> {
+        let mut processes: Vec<ProcessInfo> = ctx.sys
+            .processes()
+            .iter()
+            .map(|(pid, process)| ProcessInfo::from_process(pid.as_u32(), process))
+            .collect();
+
+        // Counting fds means a directory listing per process, so only do it when sorting by them
+        if self.options.sort_by == ProcessSortKey::Fds {
+            for process in processes.iter_mut() {
+                process.open_fds = super::process::count_open_fds(ctx.root, process.pid);
+            }
+        }
+
+        // In all-processes mode, CPU usage comes from cumulative CPU time deltas so that it
+        // covers exactly the interval between snapshots
+        if self.options.all {
+            let (prev_processes, elapsed_ms) = match ctx.rate_base(|prev| Some(&prev.all_processes)) {
+                Some((prev_all, elapsed_ms)) => {
+                    let map: HashMap<(u32, u64), u64> =
+                        prev_all.iter().map(|p| ((p.pid, p.start_time), p.cpu_time_ms)).collect();
+                    (map, elapsed_ms)
+                }
+                None => (HashMap::new(), 0.0),
+            };
+
+            processes = processes
+                .into_iter()
+                .map(|mut process| {
+                    let Some(cpu_time_ms) = super::process::read_cpu_time_ms(ctx.root, process.pid) else {
+                        return process;
+                    };
+                    process.cpu_time_ms = cpu_time_ms;
+                    // Keyed by start time too, so a recycled PID doesn't inherit the old CPU time
+                    match prev_processes.get(&(process.pid, process.start_time)) {
+                        Some(&prev_cpu_time_ms) => process.with_cpu_delta(prev_cpu_time_ms, elapsed_ms),
+                        None => process,
+                    }
+                })
+                .collect();
+        }
+
+        super::process::sort_processes(&mut processes, self.options.sort_by);
+
+        snapshot.top_processes = processes.iter().take(self.options.top_n).cloned().collect();
+        snapshot.all_processes = if self.options.all {
+            processes.iter().map(ProcessRow::from).collect()
+        } else {
+            Vec::new()
+        };
+        Ok(())
+    }
+}
+
+struct ProcessTreeCollector;
+
+impl Collector for ProcessTreeCollector {
+    fn name(&self) -> &'static str {
+        "process_tree"
+    }
+
+    // Walking every process is expensive and the tree changes slowly
+    fn interval(&self) -> Option<Duration> {
+        Some(Duration::from_secs(5))
+    }
+
+    fn refresh_kind(&self) -> RefreshKind {
+        RefreshKind::new().with_processes(ProcessRefreshKind::new().with_cpu().with_memory())
+    }
+
+    fn collect(&mut self, ctx: &CollectContext, snapshot: &mut Snapshot) -> anyhow::Result<()> {
+        snapshot.process_tree = build_process_tree(ctx.sys);
+        Ok(())
+    }
+}
+
+struct ConnectionCollector {
+    owners: SocketOwnerCache,
+}
+
+impl Collector for ConnectionCollector {
+    fn name(&self) -> &'static str {
+        "connections"
+    }
+
+    // Reads every socket table; owners are only rescanned every few seconds anyway
+    fn interval(&self) -> Option<Duration> {
+        Some(Duration::from_secs(5))
+    }
+
+    fn collect(&mut self, ctx: &CollectContext, snapshot: &mut Snapshot) -> anyhow::Result<()> {
+        snapshot.connections = super::connections::collect_connections(ctx.root, &mut self.owners)?;
+        Ok(())
+    }
+}
+
+// cgroup v2 containers - no container daemon required
+struct ContainerCollector;
+
+impl Collector for ContainerCollector {
+    fn name(&self) -> &'static str {
+        "containers"
+    }
+
+    fn collect(&mut self, ctx: &CollectContext, snapshot: &mut Snapshot) -> anyhow::Result<()> {
+        let containers = super::containers::collect_containers(ctx.root)?;
+        snapshot.containers = match ctx.rate_base(|prev| Some(&prev.containers)) {
+            Some((prev_containers, elapsed_ms)) => containers
+                .into_iter()
+                .map(|c| match prev_containers.iter().find(|p| p.cgroup_path == c.cgroup_path) {
+                    Some(prev_c) => c.with_cpu_usage(prev_c, elapsed_ms),
+                    None => c,
+                })
+                .collect(),
+            None => containers,
+        };
+        Ok(())
+    }
+}
+
+fn build_process_tree(sys: &sysinfo::System) -> Vec<ProcessTreeNode> {
+    let processes = sys.processes();
+
+    // Create a map of all processes for easy lookup
+    let mut process_map: std::collections::HashMap<u32, ProcessTreeNode> = std::collections::HashMap::new();
+    let mut parent_map: std::collections::HashMap<u32, u32> = std::collections::HashMap::new();
+
+    // First, create ProcessTreeNode for each process and track parent relationships
+    for (pid, process) in processes {
+        let pid_u32 = pid.as_u32();
+        let parent_pid = process.parent().map(|p| p.as_u32());
+
+        let node = ProcessTreeNode::new(
+            pid_u32,
+            process.name().to_string(),
+            process.cpu_usage(),
+            process.memory(),
+            parent_pid,
+        );
+
+        process_map.insert(pid_u32, node);
+
+        if let Some(parent_pid) = parent_pid {
+            parent_map.insert(pid_u32, parent_pid);
+        }
+    }
+
+    // Then, build the tree structure by assigning children to their parents
+    for (child_pid, parent_pid) in parent_map {
+        if let (Some(child_node), Some(parent_node)) = (process_map.remove(&child_pid), process_map.get_mut(&parent_pid)) {
+            parent_node.add_child(child_node);
+        }
+    }
+
+    // Collect all remaining root processes (those not added as children)
+    process_map.into_values().collect()
+}
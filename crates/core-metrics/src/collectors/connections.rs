@@ -1,18 +1,53 @@
 use serde::{Serialize, Deserialize};
+use super::fsroot::FsRoot;
+use std::fmt;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConnectionInfo {
-    pub protocol: String,
+    pub protocol: ConnectionProtocol,
     pub local_address: String,
     pub remote_address: String,
     pub state: String,
     pub pid: Option<u32>,
     pub process_name: Option<String>,
+    #[serde(default)]
+    pub inode: u64,
+    // Bytes waiting in the socket's send/receive queues
+    #[serde(default)]
+    pub tx_queue: u64,
+    #[serde(default)]
+    pub rx_queue: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ConnectionProtocol {
+    Tcp,
+    Tcp6,
+    Udp,
+    Udp6,
+    Raw,
+    Raw6,
+    Unix,
+}
+
+impl fmt::Display for ConnectionProtocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ConnectionProtocol::Tcp => "TCP",
+            ConnectionProtocol::Tcp6 => "TCP6",
+            ConnectionProtocol::Udp => "UDP",
+            ConnectionProtocol::Udp6 => "UDP6",
+            ConnectionProtocol::Raw => "RAW",
+            ConnectionProtocol::Raw6 => "RAW6",
+            ConnectionProtocol::Unix => "UNIX",
+        };
+        f.write_str(name)
+    }
 }
 
 impl ConnectionInfo {
     pub fn new(
-        protocol: String,
+        protocol: ConnectionProtocol,
         local_address: String,
         remote_address: String,
         state: String,
@@ -26,46 +61,59 @@ impl ConnectionInfo {
             state,
             pid,
             process_name,
+            inode: 0,
+            tx_queue: 0,
+            rx_queue: 0,
         }
     }
 }
 
 #[cfg(target_os = "windows")]
-pub fn collect_connections() -> Vec<ConnectionInfo> {
+pub fn collect_connections(_root: &FsRoot, _owners: &mut SocketOwnerCache) -> anyhow::Result<Vec<ConnectionInfo>> {
     // Windows implementation using sysinfo or Windows APIs
     // For now, return empty vector as sysinfo doesn't provide connection info
-    Vec::new()
+    Ok(Vec::new())
 }
 
 #[cfg(target_os = "linux")]
-pub fn collect_connections() -> Vec<ConnectionInfo> {
+pub fn collect_connections(root: &FsRoot, owners: &mut SocketOwnerCache) -> anyhow::Result<Vec<ConnectionInfo>> {
     // Linux implementation reading from /proc/net/
-    use std::fs;
-    
     let mut connections = Vec::new();
     
-    // Try to read TCP connections
-    if let Ok(tcp_data) = fs::read_to_string("/proc/net/tcp") {
-        connections.extend(parse_proc_net_file(&tcp_data, "TCP"));
+    let inet_files = [
+        ("net/tcp", ConnectionProtocol::Tcp),
+        ("net/tcp6", ConnectionProtocol::Tcp6),
+        ("net/udp", ConnectionProtocol::Udp),
+        ("net/udp6", ConnectionProtocol::Udp6),
+        ("net/raw", ConnectionProtocol::Raw),
+        ("net/raw6", ConnectionProtocol::Raw6),
+    ];
+    
+    // Missing files (e.g. IPv6 disabled) are skipped
+    for (path, protocol) in inet_files {
+        if let Some(data) = root.optional_proc(path)? {
+            connections.extend(parse_proc_net_file(&data, protocol));
+        }
     }
     
-    // Try to read UDP connections
-    if let Ok(udp_data) = fs::read_to_string("/proc/net/udp") {
-        connections.extend(parse_proc_net_file(&udp_data, "UDP"));
+    if let Some(unix_data) = root.optional_proc("net/unix")? {
+        connections.extend(parse_proc_net_unix(&unix_data));
     }
     
-    connections
+    owners.resolve(root, &mut connections, std::time::Instant::now());
+    
+    Ok(connections)
 }
 
 #[cfg(target_os = "macos")]
-pub fn collect_connections() -> Vec<ConnectionInfo> {
+pub fn collect_connections(_root: &FsRoot, _owners: &mut SocketOwnerCache) -> anyhow::Result<Vec<ConnectionInfo>> {
     // macOS implementation using system calls or lsof
     // For now, return empty vector
-    Vec::new()
+    Ok(Vec::new())
 }
 
 #[cfg(target_os = "linux")]
-fn parse_proc_net_file(data: &str, protocol: &str) -> Vec<ConnectionInfo> {
+fn parse_proc_net_file(data: &str, protocol: ConnectionProtocol) -> Vec<ConnectionInfo> {
     let mut connections = Vec::new();
     
     // Skip the header line
@@ -80,41 +128,209 @@ fn parse_proc_net_file(data: &str, protocol: &str) -> Vec<ConnectionInfo> {
         let remote_addr = parse_address(fields[2]);
         let state = parse_tcp_state(fields[3]);
         
-        connections.push(ConnectionInfo::new(
-            protocol.to_string(),
+        let mut conn = ConnectionInfo::new(
+            protocol,
             local_addr,
             remote_addr,
             state,
-            None, // PID not available in this file
-            None, // Process name not available in this file
-        ));
+            None, // Filled in by SocketOwnerCache::resolve
+            None,
+        );
+        // "tx_queue:rx_queue" in hex
+        if let Some((tx, rx)) = fields[4].split_once(':') {
+            conn.tx_queue = u64::from_str_radix(tx, 16).unwrap_or(0);
+            conn.rx_queue = u64::from_str_radix(rx, 16).unwrap_or(0);
+        }
+        conn.inode = fields[9].parse().unwrap_or(0);
+        connections.push(conn);
     }
     
     connections
 }
 
+// /proc/net/unix has its own layout:
+// Num RefCount Protocol Flags Type St Inode [Path]
 #[cfg(target_os = "linux")]
-fn parse_address(addr_str: &str) -> String {
-    let parts: Vec<&str> = addr_str.split(':').collect();
-    if parts.len() != 2 {
-        return addr_str.to_string();
-    }
+fn parse_proc_net_unix(data: &str) -> Vec<ConnectionInfo> {
+    // __SO_ACCEPTCON, set on listening sockets
+    const SO_ACCEPTCON: u32 = 0x10000;
     
-    let ip_hex = parts[0];
-    let port_hex = parts[1];
+    let mut connections = Vec::new();
+    
+    for line in data.lines().skip(1) {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 7 {
+            continue;
+        }
+        
+        let flags = u32::from_str_radix(fields[3], 16).unwrap_or(0);
+        let socket_type = match fields[4] {
+            "0001" => "STREAM",
+            "0002" => "DGRAM",
+            "0005" => "SEQPACKET",
+            _ => "UNKNOWN",
+        };
+        let state = if flags & SO_ACCEPTCON != 0 {
+            "LISTEN"
+        } else {
+            match fields[5] {
+                "01" => "UNCONNECTED",
+                "02" => "CONNECTING",
+                "03" => "CONNECTED",
+                "04" => "DISCONNECTING",
+                _ => "UNKNOWN",
+            }
+        };
+        // Unnamed sockets have no path; abstract ones start with '@'. Paths may contain spaces
+        let path = fields[7..].join(" ");
+        
+        let mut conn = ConnectionInfo::new(
+            ConnectionProtocol::Unix,
+            path,
+            String::new(),
+            format!("{} {}", socket_type, state),
+            None,
+            None,
+        );
+        conn.inode = fields[6].parse().unwrap_or(0);
+        connections.push(conn);
+    }
     
-    // Parse IP address (assuming IPv4 for simplicity)
-    if ip_hex.len() == 8 {
-        let ip_bytes = (0..4)
-            .map(|i| u8::from_str_radix(&ip_hex[i*2..i*2+2], 16).unwrap_or(0))
-            .collect::<Vec<u8>>();
+    connections
+}
+
+// Minimum time between full /proc/<pid>/fd scans; sockets opened since the last
+// scan stay unattributed until the next one
+#[cfg(target_os = "linux")]
+const SOCKET_SCAN_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Socket inode → owning PID and process name, from the socket:[inode] links in
+/// /proc/<pid>/fd. Walking every fd of every process is expensive, so results are kept
+/// between calls and only rescanned when an inode shows up that the last scan didn't
+/// already look for, and that scan is old enough.
+#[derive(Debug, Default)]
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub struct SocketOwnerCache {
+    root: Option<FsRoot>,   // What the cached owners were scanned from
+    owners: std::collections::HashMap<u64, (u32, String)>,
+    // Inodes the last scan was looking for but found no owner for: other users' sockets
+    // when not running as root, kernel sockets. Rescanning won't find them either
+    unresolved: std::collections::HashSet<u64>,
+    last_scan: Option<std::time::Instant>,
+}
+
+#[cfg(target_os = "linux")]
+impl SocketOwnerCache {
+    fn resolve(&mut self, root: &FsRoot, connections: &mut [ConnectionInfo], now: std::time::Instant) {
+        if self.root.as_ref() != Some(root) {
+            *self = Self { root: Some(root.clone()), ..Default::default() };
+        }
+        let has_new = connections.iter().any(|c| {
+            c.inode != 0 && !self.owners.contains_key(&c.inode) && !self.unresolved.contains(&c.inode)
+        });
+        let scan_due = !matches!(self.last_scan, Some(t) if now.duration_since(t) < SOCKET_SCAN_INTERVAL);
         
-        let ip = std::net::IpAddr::V4(std::net::Ipv4Addr::new(ip_bytes[0], ip_bytes[1], ip_bytes[2], ip_bytes[3]));
-        let port = u16::from_str_radix(port_hex, 16).unwrap_or(0);
+        if has_new && scan_due {
+            self.owners = scan_socket_owners(root);
+            self.unresolved = connections
+                .iter()
+                .map(|c| c.inode)
+                .filter(|inode| *inode != 0 && !self.owners.contains_key(inode))
+                .collect();
+            self.last_scan = Some(now);
+        }
         
-        format!("{}:{}", ip, port)
-    } else {
-        addr_str.to_string()
+        for conn in connections.iter_mut() {
+            if let Some((pid, name)) = self.owners.get(&conn.inode) {
+                conn.pid = Some(*pid);
+                conn.process_name = Some(name.clone());
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn scan_socket_owners(root: &FsRoot) -> std::collections::HashMap<u64, (u32, String)> {
+    use std::fs;
+    
+    let mut owners = std::collections::HashMap::new();
+    let Ok(proc_entries) = fs::read_dir(&root.proc) else {
+        return owners;
+    };
+    
+    for entry in proc_entries.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|s| s.parse::<u32>().ok()) else {
+            continue;
+        };
+        // Other users' fd directories are unreadable without privileges
+        let Ok(fds) = fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+        
+        let mut name: Option<String> = None;
+        for fd in fds.flatten() {
+            let Ok(target) = fs::read_link(fd.path()) else {
+                continue;
+            };
+            let target = target.to_string_lossy();
+            let Some(inode) = target
+                .strip_prefix("socket:[")
+                .and_then(|rest| rest.strip_suffix(']'))
+                .and_then(|inode| inode.parse::<u64>().ok())
+            else {
+                continue;
+            };
+            
+            let name = name.get_or_insert_with(|| {
+                fs::read_to_string(entry.path().join("comm"))
+                    .map(|comm| comm.trim_end().to_string())
+                    .unwrap_or_default()
+            });
+            // A socket shared across fork() is attributed to the first process seen
+            owners.entry(inode).or_insert_with(|| (pid, name.clone()));
+        }
+    }
+    
+    owners
+}
+
+// Addresses are "IP:PORT" in hex, with the IP printed as 32-bit words in host byte order
+#[cfg(target_os = "linux")]
+fn parse_address(addr_str: &str) -> String {
+    use std::net::{Ipv4Addr, Ipv6Addr};
+    
+    let Some((ip_hex, port_hex)) = addr_str.split_once(':') else {
+        return addr_str.to_string();
+    };
+    let port = u16::from_str_radix(port_hex, 16).unwrap_or(0);
+    
+    let word = |i: usize| {
+        ip_hex.get(i * 8..i * 8 + 8)
+            .and_then(|w| u32::from_str_radix(w, 16).ok())
+            .map(u32::to_ne_bytes)
+    };
+    
+    match ip_hex.len() {
+        8 => match word(0) {
+            Some(bytes) => format!("{}:{}", Ipv4Addr::from(bytes), port),
+            None => addr_str.to_string(),
+        },
+        32 => {
+            let mut bytes = [0u8; 16];
+            for i in 0..4 {
+                match word(i) {
+                    Some(w) => bytes[i * 4..i * 4 + 4].copy_from_slice(&w),
+                    None => return addr_str.to_string(),
+                }
+            }
+            let ip = Ipv6Addr::from(bytes);
+            // Dual-stack sockets see IPv4 peers as ::ffff:a.b.c.d
+            match ip.to_ipv4_mapped() {
+                Some(v4) => format!("{}:{}", v4, port),
+                None => format!("[{}]:{}", ip, port),
+            }
+        }
+        _ => addr_str.to_string(),
     }
 }
 
@@ -138,6 +354,143 @@ fn parse_tcp_state(state_hex: &str) -> String {
 
 // Fallback implementation for other platforms
 #[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
-pub fn collect_connections() -> Vec<ConnectionInfo> {
-    Vec::new()
-}
\ No newline at end of file
+pub fn collect_connections(_root: &FsRoot, _owners: &mut SocketOwnerCache) -> anyhow::Result<Vec<ConnectionInfo>> {
+    Ok(Vec::new())
+}
+
+// The kernel prints address words in host byte order, so expected values assume little-endian
+#[cfg(all(test, target_os = "linux", target_endian = "little"))]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_parse_address() {
+        assert_eq!(parse_address("0100007F:1F90"), "127.0.0.1:8080");
+        assert_eq!(parse_address("00000000000000000000000001000000:0016"), "[::1]:22");
+        assert_eq!(parse_address("0000000000000000FFFF00000100007F:0050"), "127.0.0.1:80");
+        assert_eq!(parse_address("B80D0120000000000000000001000000:01BB"), "[2001:db8::1]:443");
+    }
+    
+    #[test]
+    fn test_parse_proc_net_file_queues() {
+        let data = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n   0: 0100007F:1F90 00000000:0000 0A 00000000:00000011 00:00000000 00000000  1000        0 4242 1 0000000000000000 100 0 0 10 0\n";
+        let conns = parse_proc_net_file(data, ConnectionProtocol::Tcp);
+        assert_eq!(conns.len(), 1);
+        assert_eq!(conns[0].state, "LISTEN");
+        assert_eq!(conns[0].rx_queue, 0x11);
+        assert_eq!(conns[0].inode, 4242);
+    }
+    
+    #[test]
+    fn test_parse_proc_net_unix() {
+        let data = "\
+Num       RefCount Protocol Flags    Type St Inode Path
+0000000000000000: 00000002 00000000 00010000 0001 01 21684 /run/systemd/private
+0000000000000000: 00000003 00000000 00000000 0001 03 31337 @/tmp/.X11-unix/X0
+0000000000000000: 00000002 00000000 00000000 0002 01 41414
+0000000000000000: 00000002 00000000 00000000 0005 02 51515 /run/seqpacket.sock
+0000000000000000: 00000002 00000000 00010000 0001 01 61616 /home/me/My Files/app.sock
+";
+        let conns = parse_proc_net_unix(data);
+        assert_eq!(conns.len(), 5);
+        assert!(conns.iter().all(|c| c.protocol == ConnectionProtocol::Unix));
+        assert_eq!((conns[0].local_address.as_str(), conns[0].state.as_str()), ("/run/systemd/private", "STREAM LISTEN"));
+        assert_eq!(conns[0].inode, 21684);
+        // Abstract sockets keep their '@'; unnamed ones have no path
+        assert_eq!((conns[1].local_address.as_str(), conns[1].state.as_str()), ("@/tmp/.X11-unix/X0", "STREAM CONNECTED"));
+        assert_eq!((conns[2].local_address.as_str(), conns[2].state.as_str()), ("", "DGRAM UNCONNECTED"));
+        assert_eq!(conns[3].state, "SEQPACKET CONNECTING");
+        assert_eq!(conns[4].local_address, "/home/me/My Files/app.sock");
+    }
+    
+    // A /proc tree with two processes holding sockets
+    fn fake_proc(name: &str) -> std::path::PathBuf {
+        use std::fs;
+        use std::os::unix::fs::symlink;
+        
+        let dir = std::env::temp_dir().join(format!("zek-sockets-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        for (pid, comm, fds) in [(100, "nginx", vec!["socket:[1001]", "/dev/null"]), (200, "sshd", vec!["socket:[2002]"])] {
+            let fd_dir = dir.join(format!("proc/{}/fd", pid));
+            fs::create_dir_all(&fd_dir).unwrap();
+            fs::write(dir.join(format!("proc/{}/comm", pid)), format!("{}\n", comm)).unwrap();
+            for (fd, target) in fds.into_iter().enumerate() {
+                symlink(target, fd_dir.join(fd.to_string())).unwrap();
+            }
+        }
+        fs::create_dir_all(dir.join("proc/net")).unwrap();
+        dir
+    }
+    
+    fn socket(inode: u64) -> ConnectionInfo {
+        let mut conn = ConnectionInfo::new(ConnectionProtocol::Tcp, String::new(), String::new(), String::new(), None, None);
+        conn.inode = inode;
+        conn
+    }
+    
+    #[test]
+    fn test_scan_socket_owners() {
+        let dir = fake_proc("scan");
+        let owners = scan_socket_owners(&FsRoot::capture(&dir));
+        std::fs::remove_dir_all(&dir).unwrap();
+        
+        assert_eq!(owners.len(), 2);
+        assert_eq!(owners[&1001], (100, "nginx".to_string()));
+        assert_eq!(owners[&2002], (200, "sshd".to_string()));
+    }
+    
+    #[test]
+    fn test_unresolvable_inodes_dont_trigger_rescans() {
+        use std::time::{Duration, Instant};
+        
+        let dir = fake_proc("resolve");
+        let root = FsRoot::capture(&dir);
+        let mut cache = SocketOwnerCache::default();
+        let start = Instant::now();
+        
+        // 9999 belongs to nobody we can see
+        let mut conns = vec![socket(1001), socket(9999)];
+        cache.resolve(&root, &mut conns, start);
+        assert_eq!(conns[0].process_name.as_deref(), Some("nginx"));
+        assert_eq!(conns[1].pid, None);
+        assert_eq!(cache.last_scan, Some(start));
+        
+        // Long after the scan interval, but nothing new: no rescan
+        let later = start + Duration::from_secs(60);
+        cache.resolve(&root, &mut [socket(1001), socket(9999)], later);
+        assert_eq!(cache.last_scan, Some(start));
+        
+        // A socket opened since then does trigger one
+        std::os::unix::fs::symlink("socket:[3003]", dir.join("proc/200/fd/9")).unwrap();
+        let mut conns = vec![socket(9999), socket(3003)];
+        cache.resolve(&root, &mut conns, later);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(cache.last_scan, Some(later));
+        assert_eq!(conns[1].process_name.as_deref(), Some("sshd"));
+        assert_eq!(conns[0].pid, None);
+    }
+    
+    #[test]
+    fn test_owners_are_per_root() {
+        use std::time::Instant;
+        
+        let (first, second) = (fake_proc("root-a"), fake_proc("root-b"));
+        // Same inode, different owner in the second tree
+        std::fs::remove_file(second.join("proc/200/fd/0")).unwrap();
+        std::os::unix::fs::symlink("socket:[1001]", second.join("proc/200/fd/0")).unwrap();
+        std::fs::remove_file(second.join("proc/100/fd/0")).unwrap();
+        
+        let mut cache = SocketOwnerCache::default();
+        let now = Instant::now();
+        let mut conns = vec![socket(1001)];
+        cache.resolve(&FsRoot::capture(&first), &mut conns, now);
+        assert_eq!(conns[0].process_name.as_deref(), Some("nginx"));
+        
+        // Handed another root straight away, it scans that one rather than reusing owners
+        let mut conns = vec![socket(1001)];
+        cache.resolve(&FsRoot::capture(&second), &mut conns, now);
+        std::fs::remove_dir_all(&first).unwrap();
+        std::fs::remove_dir_all(&second).unwrap();
+        assert_eq!(conns[0].process_name.as_deref(), Some("sshd"));
+    }
+}
@@ -1,4 +1,5 @@
 use serde::{Serialize, Deserialize};
+use super::fsroot::FsRoot;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContainerInfo {
@@ -12,6 +13,28 @@ pub struct ContainerInfo {
     pub network_tx_bytes: u64,
     pub disk_read_bytes: u64,
     pub disk_write_bytes: u64,
+    // cgroup v2 details (Linux)
+    #[serde(default)]
+    pub runtime: ContainerRuntime,
+    #[serde(default)]
+    pub cgroup_path: String,
+    #[serde(default)]
+    pub cpu_usage_usec: u64,   // Cumulative, used to calculate cpu_usage_percent
+    #[serde(default)]
+    pub cpu_nr_throttled: u64,
+    #[serde(default)]
+    pub cpu_throttled_usec: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ContainerRuntime {
+    Docker,
+    Containerd,
+    CriO,
+    Podman,
+    Systemd,
+    #[default]
+    Unknown,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -47,6 +70,303 @@ impl ContainerInfo {
             network_tx_bytes,
             disk_read_bytes,
             disk_write_bytes,
+            runtime: ContainerRuntime::Unknown,
+            cgroup_path: String::new(),
+            cpu_usage_usec: 0,
+            cpu_nr_throttled: 0,
+            cpu_throttled_usec: 0,
+        }
+    }
+    
+    // Calculate CPU usage (percent of one core, like `docker stats`) based on previous snapshot
+    pub fn with_cpu_usage(mut self, previous: &ContainerInfo, time_delta_ms: f64) -> Self {
+        if time_delta_ms > 0.0 {
+            let usage_delta = self.cpu_usage_usec.saturating_sub(previous.cpu_usage_usec);
+            self.cpu_usage_percent = (usage_delta as f64 / (time_delta_ms * 1000.0) * 100.0) as f32;
+        }
+        self
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn collect_containers(root: &FsRoot) -> anyhow::Result<Vec<ContainerInfo>> {
+    use anyhow::Context;
+
+    // Pure v2 hosts mount the unified hierarchy at /sys/fs/cgroup, hybrid ones under unified/
+    let cgroup_root = ["fs/cgroup", "fs/cgroup/unified"]
+        .iter()
+        .map(|p| root.sys(p))
+        .find(|p| p.join("cgroup.controllers").exists());
+    
+    let mut containers = Vec::new();
+    if let Some(cgroup_root) = cgroup_root {
+        walk_cgroups(&cgroup_root, &cgroup_root, 0, &mut containers)
+            .with_context(|| format!("listing {}", cgroup_root.display()))?;
+    }
+    Ok(containers)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn collect_containers(_root: &FsRoot) -> anyhow::Result<Vec<ContainerInfo>> {
+    Ok(Vec::new())
+}
+
+// Deep enough for kubepods.slice/kubepods-burstable.slice/<pod>.slice/<container>.scope
+#[cfg(target_os = "linux")]
+const MAX_CGROUP_DEPTH: usize = 6;
+
+#[cfg(target_os = "linux")]
+fn walk_cgroups(root: &std::path::Path, dir: &std::path::Path, depth: usize, out: &mut Vec<ContainerInfo>) -> std::io::Result<()> {
+    let entries = std::fs::read_dir(dir)?;
+    
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+        let parent = dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        
+        match identify_container(&parent, &name) {
+            // Don't descend into a container's own sub-cgroups
+            Some((runtime, id, display_name)) => {
+                let cgroup_path = format!("/{}", path.strip_prefix(root).unwrap_or(&path).display());
+                out.push(read_container_cgroup(&path, runtime, id, display_name, cgroup_path));
+            }
+            // A cgroup removed while we walk is simply skipped
+            None if depth < MAX_CGROUP_DEPTH => {
+                let _ = walk_cgroups(root, &path, depth + 1, out);
+            }
+            None => {}
         }
     }
+    Ok(())
+}
+
+// Map a cgroup directory name to (runtime, id, name) using the naming conventions of
+// the systemd and cgroupfs drivers of the common runtimes
+#[cfg(target_os = "linux")]
+fn identify_container(parent: &str, name: &str) -> Option<(ContainerRuntime, String, String)> {
+    let scoped = |prefix: &str| {
+        name.strip_prefix(prefix)
+            .and_then(|rest| rest.strip_suffix(".scope"))
+            .filter(|id| is_container_id(id))
+    };
+    
+    let (runtime, id) = if let Some(id) = scoped("docker-") {
+        (ContainerRuntime::Docker, id)
+    } else if parent == "docker" && is_container_id(name) {
+        (ContainerRuntime::Docker, name)
+    } else if let Some(id) = scoped("cri-containerd-") {
+        (ContainerRuntime::Containerd, id)
+    } else if let Some(id) = scoped("crio-") {
+        (ContainerRuntime::CriO, id)
+    } else if let Some(id) = scoped("libpod-") {
+        (ContainerRuntime::Podman, id)
+    } else if parent == "libpod_parent" && name.starts_with("libpod-") && is_container_id(&name[7..]) {
+        (ContainerRuntime::Podman, &name[7..])
+    } else if parent == "machine.slice" && name.starts_with("machine-") && name.ends_with(".scope") {
+        // systemd-nspawn / machined guests: the unit name carries the machine name
+        let machine = unescape_unit_name(&name["machine-".len()..name.len() - ".scope".len()]);
+        return Some((ContainerRuntime::Systemd, machine.clone(), machine));
+    } else {
+        return None;
+    };
+    
+    let short_id = &id[..12];
+    let runtime_name = match runtime {
+        ContainerRuntime::Docker => "docker",
+        ContainerRuntime::Containerd => "containerd",
+        ContainerRuntime::CriO => "crio",
+        ContainerRuntime::Podman => "podman",
+        _ => "container",
+    };
+    Some((runtime, id.to_string(), format!("{}:{}", runtime_name, short_id)))
+}
+
+#[cfg(target_os = "linux")]
+fn is_container_id(s: &str) -> bool {
+    s.len() == 64 && s.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+// systemd escapes '-' and other specials in unit names as \xNN
+#[cfg(target_os = "linux")]
+fn unescape_unit_name(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(pos) = rest.find("\\x") {
+        out.push_str(&rest[..pos]);
+        let hex = rest.get(pos + 2..pos + 4).and_then(|h| u8::from_str_radix(h, 16).ok());
+        match hex {
+            Some(byte) => {
+                out.push(byte as char);
+                rest = &rest[pos + 4..];
+            }
+            None => {
+                out.push_str("\\x");
+                rest = &rest[pos + 2..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(target_os = "linux")]
+fn read_container_cgroup(
+    path: &std::path::Path,
+    runtime: ContainerRuntime,
+    id: String,
+    name: String,
+    cgroup_path: String,
+) -> ContainerInfo {
+    use std::fs;
+    
+    let read = |file: &str| fs::read_to_string(path.join(file)).unwrap_or_default();
+    let keyed = |data: &str, key: &str| {
+        data.lines()
+            .filter_map(|l| l.split_once(' '))
+            .find(|(k, _)| *k == key)
+            .and_then(|(_, v)| v.trim().parse::<u64>().ok())
+            .unwrap_or(0)
+    };
+    
+    let cpu_stat = read("cpu.stat");
+    let memory_usage = read("memory.current").trim().parse().unwrap_or(0);
+    // "max" means no limit, reported as 0
+    let memory_limit = read("memory.max").trim().parse().unwrap_or(0);
+    
+    // io.stat has one line per device: "8:0 rbytes=1 wbytes=2 rios=3 wios=4 ..."
+    let (mut disk_read, mut disk_write) = (0u64, 0u64);
+    for line in read("io.stat").lines() {
+        for field in line.split_whitespace().skip(1) {
+            match field.split_once('=') {
+                Some(("rbytes", v)) => disk_read += v.parse().unwrap_or(0),
+                Some(("wbytes", v)) => disk_write += v.parse().unwrap_or(0),
+                _ => {}
+            }
+        }
+    }
+    
+    let events = read("cgroup.events");
+    let state = if keyed(&events, "frozen") == 1 {
+        ContainerState::Paused
+    } else if keyed(&events, "populated") == 1 {
+        ContainerState::Running
+    } else {
+        ContainerState::Stopped
+    };
+    
+    let mut info = ContainerInfo::new(
+        id,
+        name,
+        state,
+        0.0, // Calculated when comparing with previous snapshots
+        memory_usage,
+        memory_limit,
+        0, // Network counters live in the container's netns, not in the cgroup
+        0,
+        disk_read,
+        disk_write,
+    );
+    info.runtime = runtime;
+    info.cgroup_path = cgroup_path;
+    info.cpu_usage_usec = keyed(&cpu_stat, "usage_usec");
+    info.cpu_nr_throttled = keyed(&cpu_stat, "nr_throttled");
+    info.cpu_throttled_usec = keyed(&cpu_stat, "throttled_usec");
+    info
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    
+    const ID: &str = "4f1c2b9e8d7a6f5e4d3c2b1a0f9e8d7c6b5a4f3e2d1c0b9a8f7e6d5c4b3a2f1e";
+    
+    #[test]
+    fn test_identify_container_naming_conventions() {
+        let docker = identify_container("system.slice", &format!("docker-{}.scope", ID)).unwrap();
+        assert_eq!(docker.0, ContainerRuntime::Docker);
+        assert_eq!(docker.1, ID);
+        assert_eq!(docker.2, "docker:4f1c2b9e8d7a");
+        
+        let cgroupfs = identify_container("docker", ID).unwrap();
+        assert_eq!(cgroupfs.0, ContainerRuntime::Docker);
+        
+        let k8s = identify_container("kubepods-burstable-pod1234.slice", &format!("cri-containerd-{}.scope", ID)).unwrap();
+        assert_eq!(k8s.0, ContainerRuntime::Containerd);
+        
+        let podman = identify_container("machine.slice", &format!("libpod-{}.scope", ID)).unwrap();
+        assert_eq!(podman.0, ContainerRuntime::Podman);
+        
+        let machine = identify_container("machine.slice", "machine-debian\\x2dtest.scope").unwrap();
+        assert_eq!(machine.0, ContainerRuntime::Systemd);
+        assert_eq!(machine.2, "debian-test");
+        
+        assert!(identify_container("machine.slice", &format!("libpod-conmon-{}.scope", ID)).is_none());
+        assert!(identify_container("system.slice", "sshd.service").is_none());
+    }
+    
+    #[test]
+    fn test_cgroup_v2_tree_over_two_samples() {
+        use std::fs;
+        
+        let dir = std::env::temp_dir().join(format!("zek-cgroups-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let cgroups = dir.join("sys/fs/cgroup");
+        let write = |cgroup: &str, files: &[(&str, &str)]| {
+            let path = cgroups.join(cgroup);
+            fs::create_dir_all(&path).unwrap();
+            for (file, value) in files {
+                fs::write(path.join(file), value).unwrap();
+            }
+        };
+        write("", &[("cgroup.controllers", "cpuset cpu io memory pids\n")]);
+        let docker = format!("system.slice/docker-{}.scope", ID);
+        write(&docker, &[
+            ("cpu.stat", "usage_usec 5000000\nuser_usec 4000000\nsystem_usec 1000000\nnr_periods 50\nnr_throttled 3\nthrottled_usec 120000\n"),
+            ("memory.current", "104857600\n"),
+            ("memory.max", "max\n"),
+            ("io.stat", "8:0 rbytes=4096 wbytes=8192 rios=1 wios=2 dbytes=0 dios=0\n259:0 rbytes=1000 wbytes=24 rios=3 wios=1 dbytes=0 dios=0\n"),
+            ("cgroup.events", "populated 1\nfrozen 0\n"),
+        ]);
+        let paused = format!("machine.slice/libpod-{}.scope", ID.replace('4', "a"));
+        write(&paused, &[
+            ("memory.max", "536870912\n"),
+            ("cgroup.events", "populated 1\nfrozen 1\n"),
+        ]);
+        // Not a container, and not listed
+        write("system.slice/sshd.service", &[("cgroup.events", "populated 1\nfrozen 0\n")]);
+        
+        let root = FsRoot::capture(&dir);
+        let mut before = collect_containers(&root).unwrap();
+        before.sort_by(|a, b| a.cgroup_path.cmp(&b.cgroup_path));
+        // Two seconds later the container has used another half second of CPU
+        write(&docker, &[("cpu.stat", "usage_usec 6000000\nnr_periods 70\nnr_throttled 5\nthrottled_usec 150000\n")]);
+        let mut after = collect_containers(&root).unwrap();
+        after.sort_by(|a, b| a.cgroup_path.cmp(&b.cgroup_path));
+        fs::remove_dir_all(&dir).unwrap();
+        
+        assert_eq!(after.len(), 2);
+        let podman = &after[0];
+        assert_eq!(podman.runtime, ContainerRuntime::Podman);
+        assert!(matches!(podman.state, ContainerState::Paused));
+        assert_eq!(podman.memory_limit_bytes, 512 * 1024 * 1024);
+        // Controllers without files read as zero
+        assert_eq!((podman.cpu_usage_usec, podman.disk_read_bytes), (0, 0));
+        
+        let container = after[1].clone().with_cpu_usage(&before[1], 2000.0);
+        assert_eq!(container.cgroup_path, format!("/{}", docker));
+        assert!(matches!(container.state, ContainerState::Running));
+        assert_eq!(container.memory_usage_bytes, 100 * 1024 * 1024);
+        // "max" is no limit
+        assert_eq!(container.memory_limit_bytes, 0);
+        // Summed across both devices
+        assert_eq!((container.disk_read_bytes, container.disk_write_bytes), (5096, 8216));
+        assert_eq!((container.cpu_nr_throttled, container.cpu_throttled_usec), (5, 150000));
+        assert_eq!(container.cpu_usage_percent, 50.0);
+        
+        // A restarted container's counter goes backwards: idle, not a wrapped rate
+        assert_eq!(before[1].clone().with_cpu_usage(&after[1], 2000.0).cpu_usage_percent, 0.0);
+    }
 }
\ No newline at end of file
@@ -1,4 +1,5 @@
 use serde::{Serialize, Deserialize};
+use super::fsroot::FsRoot;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CpuMemInfo {
@@ -7,4 +8,205 @@ pub struct CpuMemInfo {
     pub memory_total: u64,
     pub swap_used: u64,
     pub swap_total: u64,
-}
\ No newline at end of file
+}
+
+/// Cumulative CPU time from one line of /proc/stat, in clock ticks.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct CpuTimes {
+    pub user: u64,
+    pub nice: u64,
+    pub system: u64,
+    pub idle: u64,
+    pub iowait: u64,
+    pub irq: u64,
+    pub softirq: u64,
+    pub steal: u64,
+    pub guest: u64,
+    pub guest_nice: u64,
+}
+
+impl CpuTimes {
+    // guest and guest_nice are already included in user and nice
+    fn total(&self) -> u64 {
+        self.user + self.nice + self.system + self.idle + self.iowait + self.irq + self.softirq + self.steal
+    }
+}
+
+/// Share of the interval spent in each CPU state, in percent.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CpuBreakdown {
+    pub user: f64,
+    pub nice: f64,
+    pub system: f64,
+    pub idle: f64,
+    pub iowait: f64,
+    pub irq: f64,
+    pub softirq: f64,
+    pub steal: f64,
+    pub guest: f64,
+}
+
+impl CpuBreakdown {
+    // Everything except idle and iowait
+    pub fn busy(&self) -> f64 {
+        (100.0 - self.idle - self.iowait).max(0.0)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CpuCoreStats {
+    pub cpu: String,   // "cpu" for the aggregate line, "cpuN" for cores
+    pub times: CpuTimes,
+    // Calculated when comparing with previous snapshots
+    pub breakdown: Option<CpuBreakdown>,
+    pub frequency_mhz: Option<f64>,
+    pub core_throttle_count: Option<u64>,
+    pub package_throttle_count: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CpuStats {
+    pub total: CpuCoreStats,
+    pub cores: Vec<CpuCoreStats>,
+}
+
+impl CpuCoreStats {
+    // Calculate the per-state breakdown based on previous snapshot
+    pub fn with_breakdown(mut self, previous: &CpuCoreStats) -> Self {
+        let now = &self.times;
+        let before = &previous.times;
+        let total = now.total().saturating_sub(before.total());
+        if total == 0 {
+            return self;
+        }
+        let pct = |a: u64, b: u64| a.saturating_sub(b) as f64 / total as f64 * 100.0;
+        self.breakdown = Some(CpuBreakdown {
+            user: pct(now.user, before.user),
+            nice: pct(now.nice, before.nice),
+            system: pct(now.system, before.system),
+            idle: pct(now.idle, before.idle),
+            iowait: pct(now.iowait, before.iowait),
+            irq: pct(now.irq, before.irq),
+            softirq: pct(now.softirq, before.softirq),
+            steal: pct(now.steal, before.steal),
+            guest: pct(now.guest + now.guest_nice, before.guest + before.guest_nice),
+        });
+        self
+    }
+}
+
+impl CpuStats {
+    // Calculate breakdowns for the aggregate and every core based on previous snapshot
+    pub fn with_breakdown(mut self, previous: &CpuStats) -> Self {
+        self.total = self.total.with_breakdown(&previous.total);
+        self.cores = self
+            .cores
+            .into_iter()
+            .map(|core| match previous.cores.iter().find(|p| p.cpu == core.cpu) {
+                Some(prev) => core.with_breakdown(prev),
+                None => core,
+            })
+            .collect();
+        self
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn collect_cpu_stats(root: &FsRoot) -> anyhow::Result<Option<CpuStats>> {
+    let data = root.require_proc("stat")?;
+    let mut stats = CpuStats::default();
+
+    for line in data.lines().take_while(|l| l.starts_with("cpu")) {
+        let mut fields = line.split_whitespace();
+        let Some(cpu) = fields.next() else {
+            continue;
+        };
+        let values: Vec<u64> = fields.map(|v| v.parse().unwrap_or(0)).collect();
+        let value = |i: usize| values.get(i).copied().unwrap_or(0);
+        let times = CpuTimes {
+            user: value(0),
+            nice: value(1),
+            system: value(2),
+            idle: value(3),
+            iowait: value(4),
+            irq: value(5),
+            softirq: value(6),
+            steal: value(7),
+            guest: value(8),
+            guest_nice: value(9),
+        };
+
+        if cpu == "cpu" {
+            stats.total = CpuCoreStats { cpu: cpu.to_string(), times, ..Default::default() };
+            continue;
+        }
+
+        let sys_dir = format!("devices/system/cpu/{}", cpu);
+        let read_u64 = |file: &str| {
+            root.read_sys(&format!("{}/{}", sys_dir, file))
+                .ok()
+                .and_then(|v| v.trim().parse::<u64>().ok())
+        };
+        stats.cores.push(CpuCoreStats {
+            cpu: cpu.to_string(),
+            times,
+            breakdown: None,
+            // scaling_cur_freq is in kHz
+            frequency_mhz: read_u64("cpufreq/scaling_cur_freq").map(|khz| khz as f64 / 1000.0),
+            core_throttle_count: read_u64("thermal_throttle/core_throttle_count"),
+            package_throttle_count: read_u64("thermal_throttle/package_throttle_count"),
+        });
+    }
+
+    Ok(Some(stats))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn collect_cpu_stats(_root: &FsRoot) -> anyhow::Result<Option<CpuStats>> {
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn core(cpu: &str, times: CpuTimes) -> CpuCoreStats {
+        CpuCoreStats { cpu: cpu.to_string(), times, ..Default::default() }
+    }
+
+    #[test]
+    fn test_breakdown_between_samples() {
+        let before = core("cpu0", CpuTimes { user: 1000, system: 500, idle: 8000, guest: 100, ..Default::default() });
+        // 1000 ticks later: 400 user of which 200 were spent running a guest
+        let after = core("cpu0", CpuTimes {
+            user: 1400,
+            system: 600,
+            idle: 8400,
+            iowait: 100,
+            guest: 300,
+            ..Default::default()
+        });
+
+        let breakdown = after.with_breakdown(&before).breakdown.unwrap();
+        assert_eq!(breakdown.user, 40.0);
+        assert_eq!(breakdown.system, 10.0);
+        assert_eq!(breakdown.idle, 40.0);
+        assert_eq!(breakdown.iowait, 10.0);
+        // Guest time is part of user, not added on top of it
+        assert_eq!(breakdown.guest, 20.0);
+        assert_eq!(breakdown.busy(), 50.0);
+    }
+
+    #[test]
+    fn test_no_breakdown_without_elapsed_time() {
+        let times = CpuTimes { user: 10, idle: 90, ..Default::default() };
+        assert!(core("cpu", times).with_breakdown(&core("cpu", times)).breakdown.is_none());
+
+        // Cores are matched by name, so a core that just came online gets none either
+        let stats = CpuStats { total: core("cpu", times), cores: vec![core("cpu1", times)] };
+        let previous = CpuStats { total: core("cpu", CpuTimes::default()), cores: vec![core("cpu0", CpuTimes::default())] };
+        let stats = stats.with_breakdown(&previous);
+        assert_eq!(stats.total.breakdown.unwrap().busy(), 10.0);
+        assert!(stats.cores[0].breakdown.is_none());
+    }
+}
@@ -1,5 +1,7 @@
 use serde::{Serialize, Deserialize};
+use super::fsroot::FsRoot;
 use sysinfo::Disk;
+use super::storage_health::DiskHealth;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DiskInfo {
@@ -9,6 +11,27 @@ pub struct DiskInfo {
     pub available_space: u64,
     pub used_space: u64,
     pub usage_percent: f32,
+    #[serde(default)]
+    pub device: Option<String>,   // Kernel block device ("sda1", "dm-0") backing the mount
+    #[serde(default)]
+    pub fs_type: String,
+    #[serde(default)]
+    pub mount_options: Vec<String>,
+    #[serde(default)]
+    pub read_only: bool,
+    #[serde(default)]
+    pub noexec: bool,
+    // Inode counts; zero for filesystems that allocate inodes dynamically (btrfs, some FUSE)
+    #[serde(default)]
+    pub inodes_total: u64,
+    #[serde(default)]
+    pub inodes_used: u64,
+    #[serde(default)]
+    pub inodes_free: u64,
+    #[serde(default)]
+    pub inode_usage_percent: f32,
+    #[serde(default)]
+    pub health: Option<DiskHealth>,   // SMART data for the drive behind this filesystem
 }
 
 impl DiskInfo {
@@ -38,6 +61,501 @@ impl DiskInfo {
             available_space: available,
             used_space: used,
             usage_percent: usage,
+            device: None,
+            fs_type: disk.file_system().to_string_lossy().to_string(),
+            mount_options: Vec::new(),
+            read_only: false,
+            noexec: false,
+            inodes_total: 0,
+            inodes_used: 0,
+            inodes_free: 0,
+            inode_usage_percent: 0.0,
+            health: None,
         }
     }
-}
\ No newline at end of file
+}
+
+/// Filesystem table settings from the `[filesystems]` section of zek.toml.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct FilesystemOptions {
+    pub exclude_fs_types: Vec<String>,
+    // Shown even when listed in exclude_fs_types, e.g. ["tmpfs"] to watch /dev/shm
+    pub include_fs_types: Vec<String>,
+    // Mount points hidden along with everything below them
+    pub exclude_mount_points: Vec<String>,
+}
+
+impl Default for FilesystemOptions {
+    fn default() -> Self {
+        let strings = |v: &[&str]| v.iter().map(|s| s.to_string()).collect();
+        Self {
+            exclude_fs_types: strings(&[
+                "autofs", "binfmt_misc", "bpf", "cgroup", "cgroup2", "configfs", "debugfs",
+                "devpts", "devtmpfs", "efivarfs", "fusectl", "hugetlbfs", "iso9660", "mqueue",
+                "nsfs", "overlay", "proc", "pstore", "ramfs", "rootfs", "rpc_pipefs",
+                "securityfs", "selinuxfs", "squashfs", "sysfs", "tmpfs", "tracefs",
+            ]),
+            include_fs_types: Vec::new(),
+            exclude_mount_points: strings(&[
+                "/proc", "/sys", "/dev", "/run/user", "/run/credentials", "/snap",
+                "/var/lib/docker", "/var/lib/containers", "/var/lib/kubelet",
+            ]),
+        }
+    }
+}
+
+impl FilesystemOptions {
+    pub fn includes(&self, fs_type: &str, mount_point: &str) -> bool {
+        if self.include_fs_types.iter().any(|t| t == fs_type) {
+            return true;
+        }
+        if self.exclude_fs_types.iter().any(|t| t == fs_type) {
+            return false;
+        }
+        !self.exclude_mount_points.iter().any(|prefix| {
+            let prefix = prefix.trim_end_matches('/');
+            mount_point == prefix || mount_point.starts_with(&format!("{}/", prefix))
+        })
+    }
+}
+
+/// One line of /proc/self/mountinfo.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MountEntry {
+    pub major_minor: String,
+    pub mount_point: String,
+    pub fs_type: String,
+    pub source: String,
+    pub options: Vec<String>,
+}
+
+// "36 35 98:0 /mnt1 /mnt2 rw,noatime master:1 - ext3 /dev/root rw,errors=continue"
+// A variable number of optional fields precede the "-" separator
+pub fn parse_mountinfo(data: &str) -> Vec<MountEntry> {
+    data.lines()
+        .filter_map(|line| {
+            let (mount, fs) = line.split_once(" - ")?;
+            let mount: Vec<&str> = mount.split_whitespace().collect();
+            let mut fs = fs.split_whitespace();
+            if mount.len() < 6 {
+                return None;
+            }
+            Some(MountEntry {
+                major_minor: mount[2].to_string(),
+                mount_point: unescape_mount_path(mount[4]),
+                options: mount[5].split(',').map(String::from).collect(),
+                fs_type: fs.next()?.to_string(),
+                source: fs.next().map(unescape_mount_path).unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+// Spaces, tabs, newlines and backslashes in paths are written as octal escapes ("\040")
+fn unescape_mount_path(path: &str) -> String {
+    let mut out = String::with_capacity(path.len());
+    let mut rest = path;
+    while let Some(pos) = rest.find('\\') {
+        out.push_str(&rest[..pos]);
+        let escape = rest.get(pos + 1..pos + 4);
+        match escape.and_then(|e| u8::from_str_radix(e, 8).ok()) {
+            Some(byte) => {
+                out.push(byte as char);
+                rest = &rest[pos + 4..];
+            }
+            None => {
+                out.push('\\');
+                rest = &rest[pos + 1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+// How long a network filesystem gets to answer statvfs before it is left out of the tick
+const NETWORK_STATVFS_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Network mounts whose statvfs is still blocked on an unreachable server. Each one keeps
+/// a helper thread stuck in the kernel, so no further calls are made for it until that
+/// thread returns.
+#[derive(Debug, Default)]
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub struct HungMounts {
+    pending: std::sync::Arc<std::sync::Mutex<std::collections::HashSet<std::path::PathBuf>>>,
+}
+
+#[cfg(target_os = "linux")]
+impl HungMounts {
+    fn statvfs(&self, path: std::path::PathBuf, fs_type: &str) -> Option<nix::sys::statvfs::Statvfs> {
+        use nix::sys::statvfs::statvfs;
+
+        if !is_network_fs(fs_type) {
+            return statvfs(&path).ok();
+        }
+        let pending = std::sync::Arc::clone(&self.pending);
+        if !pending.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).insert(path.clone()) {
+            return None;
+        }
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let stat = statvfs(&path).ok();
+            pending.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).remove(&path);
+            let _ = tx.send(stat);
+        });
+        rx.recv_timeout(NETWORK_STATVFS_TIMEOUT).ok().flatten()
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn is_network_fs(fs_type: &str) -> bool {
+    matches!(
+        fs_type,
+        "nfs" | "nfs4" | "cifs" | "smb3" | "smbfs" | "ceph" | "glusterfs" | "lustre" | "afs" | "9p"
+    ) || fs_type.starts_with("fuse.")
+}
+
+// Where a mount point from the root's mount table can be stat'ed: through init's root
+// directory when reading another procfs (a host's /proc mounted into a container)
+#[cfg(target_os = "linux")]
+fn statvfs_path(root: &FsRoot, mount_point: &str) -> std::path::PathBuf {
+    if root.proc == FsRoot::default().proc {
+        mount_point.into()
+    } else {
+        root.proc("1/root").join(mount_point.trim_start_matches('/'))
+    }
+}
+
+// Linux reads the mount table and statvfs directly; `disks`, refreshed by the caller,
+// backs the other platforms
+#[cfg(target_os = "linux")]
+pub fn collect_filesystems(root: &FsRoot, options: &FilesystemOptions, hung: &HungMounts, _disks: &sysinfo::Disks) -> anyhow::Result<Vec<DiskInfo>> {
+    let data = root.require_proc("self/mountinfo")?;
+
+    let mut disks: Vec<DiskInfo> = Vec::new();
+    for entry in parse_mountinfo(&data) {
+        if !options.includes(&entry.fs_type, &entry.mount_point) {
+            continue;
+        }
+        let Some(stat) = hung.statvfs(statvfs_path(root, &entry.mount_point), &entry.fs_type) else {
+            continue;
+        };
+        let fragment = stat.fragment_size();
+        let total = stat.blocks() * fragment;
+        let available = stat.blocks_available() * fragment;
+        let used = total.saturating_sub(available);
+        let inodes_total = stat.files();
+        let inodes_free = stat.files_free();
+        let inodes_used = inodes_total.saturating_sub(inodes_free);
+
+        // Later mounts on the same path hide earlier ones
+        disks.retain(|d| d.mount_point != entry.mount_point);
+        disks.push(DiskInfo {
+            device: device_for_mount(root, &entry),
+            name: entry.source,
+            mount_point: entry.mount_point,
+            total_space: total,
+            available_space: available,
+            used_space: used,
+            usage_percent: if total > 0 { used as f32 / total as f32 * 100.0 } else { 0.0 },
+            fs_type: entry.fs_type,
+            read_only: entry.options.iter().any(|o| o == "ro"),
+            noexec: entry.options.iter().any(|o| o == "noexec"),
+            mount_options: entry.options,
+            inodes_total,
+            inodes_used,
+            inodes_free,
+            inode_usage_percent: if inodes_total > 0 {
+                inodes_used as f32 / inodes_total as f32 * 100.0
+            } else {
+                0.0
+            },
+            health: None,
+        });
+    }
+
+    Ok(disks)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn collect_filesystems(_root: &FsRoot, options: &FilesystemOptions, _hung: &HungMounts, disks: &sysinfo::Disks) -> anyhow::Result<Vec<DiskInfo>> {
+    Ok(disks
+        .iter()
+        .map(DiskInfo::from_disk)
+        .filter(|disk| options.includes(&disk.fs_type, &disk.mount_point))
+        .collect())
+}
+
+// Resolve the block device behind a mount through /sys/dev/block/<major:minor>, falling
+// back to the mount source for filesystems on anonymous devices (btrfs subvolumes)
+#[cfg(target_os = "linux")]
+fn device_for_mount(root: &FsRoot, entry: &MountEntry) -> Option<String> {
+    let sys_path = root.sys(&format!("dev/block/{}", entry.major_minor));
+    match std::fs::canonicalize(sys_path) {
+        Ok(path) => path.file_name().map(|name| name.to_string_lossy().to_string()),
+        Err(_) => block_device_name(&entry.source),
+    }
+}
+
+/// Per-block-device I/O statistics read from /proc/diskstats.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DiskIoInfo {
+    pub device: String,
+    pub mount_points: Vec<String>,
+    // Cumulative counters as reported by the kernel
+    pub reads_completed: u64,
+    pub writes_completed: u64,
+    pub sectors_read: u64,
+    pub sectors_written: u64,
+    pub read_time_ms: u64,
+    pub write_time_ms: u64,
+    pub io_time_ms: u64,
+    pub in_flight: u64,
+    // Per-interval rates, calculated when comparing with previous snapshots
+    pub read_ops_per_sec: f64,
+    pub write_ops_per_sec: f64,
+    pub read_bytes_per_sec: f64,
+    pub write_bytes_per_sec: f64,
+    pub await_ms: f64,
+    pub util_percent: f64,
+}
+
+// /proc/diskstats always counts in 512-byte sectors, whatever the device's block size
+const SECTOR_SIZE: u64 = 512;
+
+impl DiskIoInfo {
+    // Calculate rates based on previous snapshot
+    pub fn with_rates(mut self, previous: &DiskIoInfo, time_delta_ms: f64) -> Self {
+        if time_delta_ms <= 0.0 {
+            return self;
+        }
+        let secs = time_delta_ms / 1000.0;
+
+        let reads = self.reads_completed.saturating_sub(previous.reads_completed);
+        let writes = self.writes_completed.saturating_sub(previous.writes_completed);
+        let read_sectors = self.sectors_read.saturating_sub(previous.sectors_read);
+        let write_sectors = self.sectors_written.saturating_sub(previous.sectors_written);
+        let read_time = self.read_time_ms.saturating_sub(previous.read_time_ms);
+        let write_time = self.write_time_ms.saturating_sub(previous.write_time_ms);
+        let io_time = self.io_time_ms.saturating_sub(previous.io_time_ms);
+
+        self.read_ops_per_sec = reads as f64 / secs;
+        self.write_ops_per_sec = writes as f64 / secs;
+        self.read_bytes_per_sec = (read_sectors * SECTOR_SIZE) as f64 / secs;
+        self.write_bytes_per_sec = (write_sectors * SECTOR_SIZE) as f64 / secs;
+
+        // Average time each completed request spent queued and serviced (iostat's await)
+        let ops = reads + writes;
+        self.await_ms = if ops > 0 {
+            (read_time + write_time) as f64 / ops as f64
+        } else {
+            0.0
+        };
+
+        // Share of wall-clock time the device had at least one request in flight
+        self.util_percent = (io_time as f64 / time_delta_ms * 100.0).min(100.0);
+
+        self
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn collect_disk_io(root: &FsRoot) -> anyhow::Result<Vec<DiskIoInfo>> {
+    let mut devices = parse_diskstats(&root.require_proc("diskstats")?);
+    devices.retain(|d| !is_placeholder_device(root, &d.device));
+    Ok(devices)
+}
+
+// The kernel lists every loop device whether or not anything is attached, and brd
+// ramdisks aren't storage. Idle real disks stay, so their mount points can be joined
+#[cfg(target_os = "linux")]
+fn is_placeholder_device(root: &FsRoot, device: &str) -> bool {
+    if device.strip_prefix("ram").is_some_and(|n| n.parse::<u32>().is_ok()) {
+        return true;
+    }
+    device.starts_with("loop") && !root.sys(&format!("block/{}/loop/backing_file", device)).exists()
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn collect_disk_io(_root: &FsRoot) -> anyhow::Result<Vec<DiskIoInfo>> {
+    Ok(Vec::new())
+}
+
+#[cfg(target_os = "linux")]
+fn parse_diskstats(data: &str) -> Vec<DiskIoInfo> {
+    let mut devices = Vec::new();
+
+    for line in data.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        // major minor name + at least the 11 classic counters
+        if fields.len() < 14 {
+            continue;
+        }
+
+        let counter = |i: usize| fields[i].parse::<u64>().unwrap_or(0);
+        let info = DiskIoInfo {
+            device: fields[2].to_string(),
+            reads_completed: counter(3),
+            sectors_read: counter(5),
+            read_time_ms: counter(6),
+            writes_completed: counter(7),
+            sectors_written: counter(9),
+            write_time_ms: counter(10),
+            in_flight: counter(11),
+            io_time_ms: counter(12),
+            ..Default::default()
+        };
+        devices.push(info);
+    }
+
+    devices
+}
+
+/// Fill in `mount_points` for each device from the filesystem list.
+pub fn attach_mount_points(devices: &mut [DiskIoInfo], disks: &[DiskInfo]) {
+    for disk in disks {
+        let Some(device) = disk.device.clone().or_else(|| block_device_name(&disk.name)) else {
+            continue;
+        };
+        if let Some(io) = devices.iter_mut().find(|d| d.device == device) {
+            io.mount_points.push(disk.mount_point.clone());
+        }
+    }
+}
+
+// Map a filesystem source such as "/dev/sda1" or "/dev/mapper/vg-root" to its
+// kernel device name ("sda1", "dm-0") as it appears in /proc/diskstats
+fn block_device_name(source: &str) -> Option<String> {
+    if !source.starts_with("/dev/") {
+        return None;
+    }
+    let resolved = std::fs::canonicalize(source).unwrap_or_else(|_| source.into());
+    resolved
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mountinfo() {
+        let data = "\
+22 1 259:2 / / rw,relatime shared:1 - ext4 /dev/nvme0n1p2 rw,errors=remount-ro
+95 22 0:45 / /mnt/USB\\040Drive ro,nosuid,noexec - vfat /dev/sdb1 ro,fmask=0022
+";
+        let mounts = parse_mountinfo(data);
+        assert_eq!(mounts.len(), 2);
+        assert_eq!(mounts[0].major_minor, "259:2");
+        assert_eq!(mounts[0].fs_type, "ext4");
+        assert_eq!(mounts[0].source, "/dev/nvme0n1p2");
+        assert_eq!(mounts[1].mount_point, "/mnt/USB Drive");
+        assert_eq!(mounts[1].options, vec!["ro", "nosuid", "noexec"]);
+    }
+
+    #[test]
+    fn test_filesystem_filter() {
+        let options = FilesystemOptions {
+            include_fs_types: vec!["tmpfs".to_string()],
+            ..Default::default()
+        };
+        assert!(options.includes("ext4", "/"));
+        assert!(options.includes("tmpfs", "/dev/shm"));
+        assert!(!options.includes("squashfs", "/snap/core/123"));
+        assert!(!options.includes("ext4", "/var/lib/docker/volumes"));
+        assert!(options.includes("ext4", "/snapshots"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_statvfs_of_network_and_host_mounts() {
+        let host = FsRoot { proc: "/host/proc".into(), ..Default::default() };
+        assert_eq!(statvfs_path(&FsRoot::default(), "/home"), std::path::PathBuf::from("/home"));
+        assert_eq!(statvfs_path(&host, "/home"), std::path::PathBuf::from("/host/proc/1/root/home"));
+
+        let hung = HungMounts::default();
+        assert!(hung.statvfs("/".into(), "nfs4").is_some());
+        // A mount whose earlier statvfs never returned isn't tried again
+        hung.pending.lock().unwrap().insert("/".into());
+        assert!(hung.statvfs("/".into(), "nfs4").is_none());
+        assert!(hung.statvfs("/".into(), "ext4").is_some());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_parse_diskstats() {
+        let data = "\
+   8       0 sda 12345 100 987654 5000 6789 200 456789 3000 2 4000 8000 0 0 0 0
+   8      16 sdb 0 0 0 0 0 0 0 0 0 0 0
+   7       0 loop0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0
+ 259       0 nvme0n1 1 2 3
+";
+        let devices = parse_diskstats(data);
+        assert_eq!(devices.len(), 3);
+        assert_eq!(devices[0].device, "sda");
+        assert_eq!(devices[0].reads_completed, 12345);
+        assert_eq!(devices[0].sectors_read, 987654);
+        assert_eq!(devices[0].write_time_ms, 3000);
+        assert_eq!(devices[0].in_flight, 2);
+        assert_eq!(devices[0].io_time_ms, 4000);
+        // An idle disk is still listed
+        assert_eq!(devices[1].device, "sdb");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_placeholder_devices() {
+        let dir = std::env::temp_dir().join(format!("zek-diskstats-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("sys/block/loop0/loop")).unwrap();
+        std::fs::write(dir.join("sys/block/loop0/loop/backing_file"), "/var/lib/snapd/snaps/core.snap\n").unwrap();
+        let root = FsRoot::capture(&dir);
+
+        let placeholders: Vec<bool> = ["loop0", "loop1", "ram0", "sda", "zram0"]
+            .iter()
+            .map(|d| is_placeholder_device(&root, d))
+            .collect();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(placeholders, vec![false, true, true, false, false]);
+    }
+
+    #[test]
+    fn test_disk_io_rates() {
+        let before = DiskIoInfo {
+            device: "sda".to_string(),
+            reads_completed: 100,
+            writes_completed: 50,
+            sectors_read: 1000,
+            sectors_written: 2000,
+            read_time_ms: 100,
+            write_time_ms: 100,
+            io_time_ms: 1000,
+            ..Default::default()
+        };
+        let after = DiskIoInfo {
+            reads_completed: 200,
+            writes_completed: 150,
+            sectors_read: 3000,
+            sectors_written: 6000,
+            read_time_ms: 300,
+            write_time_ms: 500,
+            io_time_ms: 1500,
+            ..before.clone()
+        };
+
+        let io = after.clone().with_rates(&before, 2000.0);
+        assert_eq!(io.read_ops_per_sec, 50.0);
+        assert_eq!(io.write_ops_per_sec, 50.0);
+        assert_eq!(io.read_bytes_per_sec, 2000.0 * 512.0 / 2.0);
+        assert_eq!(io.write_bytes_per_sec, 4000.0 * 512.0 / 2.0);
+        // 600 ms of request time over 200 requests
+        assert_eq!(io.await_ms, 3.0);
+        assert_eq!(io.util_percent, 25.0);
+
+        // An idle interval, and no interval at all
+        let idle = before.clone().with_rates(&before, 1000.0);
+        assert_eq!((idle.await_ms, idle.util_percent, idle.read_ops_per_sec), (0.0, 0.0, 0.0));
+        assert_eq!(after.with_rates(&before, 0.0).read_ops_per_sec, 0.0);
+    }
+}
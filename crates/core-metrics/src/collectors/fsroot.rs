@@ -0,0 +1,165 @@
+use anyhow::Context;
+use serde::Deserialize;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Where collectors find procfs, sysfs and /run: the host's own, a host mount inside a
+/// container (e.g. /host/proc), or a tree captured with scripts/capture_host.sh.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct FsRoot {
+    pub proc: PathBuf,
+    pub sys: PathBuf,
+    pub run: PathBuf,
+}
+
+impl Default for FsRoot {
+    fn default() -> Self {
+        Self {
+            proc: PathBuf::from("/proc"),
+            sys: PathBuf::from("/sys"),
+            run: PathBuf::from("/run"),
+        }
+    }
+}
+
+impl FsRoot {
+    // An unpacked capture holds proc/, sys/ and run/ side by side
+    pub fn capture(dir: &Path) -> Self {
+        Self {
+            proc: dir.join("proc"),
+            sys: dir.join("sys"),
+            run: dir.join("run"),
+        }
+    }
+
+    // Path of a file relative to /proc, e.g. root.proc("net/dev")
+    pub fn proc(&self, path: &str) -> PathBuf {
+        self.proc.join(path)
+    }
+
+    pub fn sys(&self, path: &str) -> PathBuf {
+        self.sys.join(path)
+    }
+
+    pub fn run(&self, path: &str) -> PathBuf {
+        self.run.join(path)
+    }
+
+    pub fn read_proc(&self, path: &str) -> io::Result<String> {
+        std::fs::read_to_string(self.proc(path))
+    }
+
+    pub fn read_sys(&self, path: &str) -> io::Result<String> {
+        std::fs::read_to_string(self.sys(path))
+    }
+
+    // A file every Linux kernel has, so failing to read it is a collector error
+    pub fn require_proc(&self, path: &str) -> anyhow::Result<String> {
+        let path = self.proc(path);
+        std::fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))
+    }
+
+    pub fn optional_proc(&self, path: &str) -> anyhow::Result<Option<String>> {
+        read_optional(&self.proc(path))
+    }
+}
+
+// Files that only exist with some kernel options or drivers (PSI, ZFS, IPv6, ...).
+// Missing or unsupported is Ok(None); any other failure is an error naming the file
+fn unavailable(e: &io::Error) -> bool {
+    matches!(e.kind(), io::ErrorKind::NotFound | io::ErrorKind::Unsupported)
+}
+
+pub fn read_optional(path: &Path) -> anyhow::Result<Option<String>> {
+    match std::fs::read_to_string(path) {
+        Ok(data) => Ok(Some(data)),
+        Err(e) if unavailable(&e) => Ok(None),
+        Err(e) => Err(e).with_context(|| format!("reading {}", path.display())),
+    }
+}
+
+pub fn read_dir_optional(path: &Path) -> anyhow::Result<Option<std::fs::ReadDir>> {
+    match std::fs::read_dir(path) {
+        Ok(entries) => Ok(Some(entries)),
+        Err(e) if unavailable(&e) => Ok(None),
+        Err(e) => Err(e).with_context(|| format!("listing {}", path.display())),
+    }
+}
+
+// Regression tests against captured trees under tests/fixtures/hosts
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::cfg::Collectors;
+    use crate::collectors::registry::CollectorRegistry;
+    use crate::collectors::snapshot::Snapshot;
+
+    fn collect_from_capture(name: &str) -> Snapshot {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/hosts").join(name);
+        // Collectors built on sysinfo or statvfs, which only ever see the live host
+        let host_only = ["system", "network", "filesystems", "sensors", "processes", "process_tree"];
+        let toggles = Collectors {
+            disabled: host_only.iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        };
+        let mut registry = CollectorRegistry::with_builtin(toggles, Default::default(), Default::default(), None)
+            .with_root(FsRoot::capture(&dir));
+        registry.collect(chrono::Utc::now(), &sysinfo::System::new(), None)
+    }
+
+    #[test]
+    fn test_capture_many_cores() {
+        let snapshot = collect_from_capture("many_cores");
+
+        let cpu_stats = snapshot.cpu_stats.unwrap();
+        assert_eq!(cpu_stats.cores.len(), 256);
+        assert_eq!(cpu_stats.cores[255].cpu, "cpu255");
+
+        let interrupts = snapshot.interrupts.unwrap();
+        let eth = interrupts.interrupts.iter().find(|i| i.irq == "130").unwrap();
+        assert_eq!(eth.counts.len(), 256);
+        assert_eq!(eth.counts[255], 1255);
+        assert_eq!(eth.device, "eth0-TxRx-0");
+        assert!(interrupts.softirqs.iter().all(|s| s.counts.len() == 256));
+
+        let topology = snapshot.topology.unwrap();
+        assert_eq!(topology.nodes.len(), 2);
+        assert_eq!(topology.nodes[1].cpus.len(), 128);
+        assert!(topology.nodes[1].cpus.contains(&255));
+
+        assert_eq!(snapshot.mem_total_bytes, 1056462848 * 1024);
+    }
+
+    #[test]
+    fn test_capture_no_swap() {
+        let snapshot = collect_from_capture("no_swap");
+
+        assert_eq!(snapshot.swap_total_bytes, 0);
+        assert_eq!(snapshot.swap_used_bytes, 0);
+        assert_eq!(snapshot.metrics()["swap_usage_percent"], 0.0);
+        assert_eq!(snapshot.mem_used_bytes, (2014232 - 1022948) * 1024);
+        assert_eq!(snapshot.vmstat.unwrap().oom_kill, 2);
+        // PSI disabled, no NUMA node directory, no network stack files
+        assert!(snapshot.pressure.is_none());
+        assert!(snapshot.topology.is_none());
+        assert!(snapshot.tcp.is_none());
+        assert_eq!(snapshot.cpu_stats.unwrap().cores.len(), 2);
+    }
+
+    #[test]
+    fn test_unreadable_files_are_collector_errors() {
+        let snapshot = collect_from_capture("does_not_exist");
+        let status = |name: &str| snapshot.collectors.iter().find(|c| c.name == name).unwrap().clone();
+
+        // Files every kernel has
+        let cpu = status("cpu");
+        assert_eq!(cpu.failures, 1);
+        assert!(cpu.last_error.unwrap().contains("does_not_exist/proc/stat"));
+        assert_eq!(status("memory").failures, 1);
+        // Files that only exist with some kernel options or drivers
+        for optional in ["pressure", "tcp", "topology", "storage", "rapl", "power_supply"] {
+            assert_eq!(status(optional).failures, 0, "{}", optional);
+        }
+    }
+}
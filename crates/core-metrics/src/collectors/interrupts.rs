@@ -0,0 +1,301 @@
+use serde::{Serialize, Deserialize};
+use super::cpu_mem::CpuStats;
+use super::fsroot::FsRoot;
+
+// Share of a CPU's time spent in softirq context (mpstat's %soft) above which it counts
+// as saturated, provided network softirqs make up most of the work
+const NET_SOFTIRQ_SATURATION_PCT: f64 = 30.0;
+
+/// Hardware interrupts from /proc/interrupts and softirqs from /proc/softirqs.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InterruptStats {
+    pub interrupts: Vec<IrqInfo>,
+    pub softirqs: Vec<SoftirqInfo>,
+    // CPU ids from each file's header: counts[i] and rates[i] belong to CPU irq_cpus[i]
+    // (or softirq_cpus[i]). Offline CPUs leave gaps, so index and id can differ
+    #[serde(default)]
+    pub irq_cpus: Vec<usize>,
+    #[serde(default)]
+    pub softirq_cpus: Vec<usize>,
+    // CPUs spending most of their time on NET_RX/NET_TX processing
+    pub net_saturated_cpus: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IrqInfo {
+    pub irq: String,          // "24", "NMI", "LOC", ...
+    pub controller: String,   // e.g. "IR-PCI-MSI 327680-edge"; empty for named interrupts
+    pub device: String,       // e.g. "eth0-TxRx-0", "Local timer interrupts"
+    pub counts: Vec<u64>,     // Cumulative count per CPU
+    // Per-second rates, calculated when comparing with previous snapshots
+    pub rates: Vec<f64>,
+    pub total_rate: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SoftirqInfo {
+    pub name: String,   // "NET_RX", "TIMER", ...
+    pub counts: Vec<u64>,
+    pub rates: Vec<f64>,
+}
+
+// Per-CPU rates from two sets of cumulative counters, matched up by CPU id since the
+// set of online CPUs may have changed in between. A CPU that just came online has no rate
+fn per_cpu_rates(now: &[u64], cpus: &[usize], before: &[u64], before_cpus: &[usize], secs: f64) -> Vec<f64> {
+    now.iter()
+        .enumerate()
+        .map(|(i, count)| {
+            let prev = cpus.get(i)
+                .and_then(|cpu| before_cpus.iter().position(|c| c == cpu))
+                .and_then(|j| before.get(j));
+            prev.map_or(0.0, |prev| count.saturating_sub(*prev) as f64 / secs)
+        })
+        .collect()
+}
+
+// Position of a CPU id in a header list
+fn cpu_index(cpus: &[usize], cpu: usize) -> Option<usize> {
+    cpus.iter().position(|c| *c == cpu)
+}
+
+impl InterruptStats {
+    // Calculate per-CPU rates based on previous snapshot
+    pub fn with_rates(mut self, previous: &InterruptStats, time_delta_ms: f64) -> Self {
+        if time_delta_ms <= 0.0 {
+            return self;
+        }
+        let secs = time_delta_ms / 1000.0;
+
+        for irq in self.interrupts.iter_mut() {
+            if let Some(prev) = previous.interrupts.iter().find(|p| p.irq == irq.irq) {
+                irq.rates = per_cpu_rates(&irq.counts, &self.irq_cpus, &prev.counts, &previous.irq_cpus, secs);
+                irq.total_rate = irq.rates.iter().sum();
+            }
+        }
+        for softirq in self.softirqs.iter_mut() {
+            if let Some(prev) = previous.softirqs.iter().find(|p| p.name == softirq.name) {
+                softirq.rates = per_cpu_rates(&softirq.counts, &self.softirq_cpus, &prev.counts, &previous.softirq_cpus, secs);
+            }
+        }
+
+        self
+    }
+
+    // Flag CPUs whose softirq time is high and mostly spent on network processing,
+    // the usual sign of poor IRQ affinity or missing RPS/RSS on packet-heavy hosts
+    pub fn flag_net_saturation(&mut self, cpu_stats: Option<&CpuStats>) {
+        let Some(cpu_stats) = cpu_stats else {
+            return;
+        };
+        let rate = |name: &str, index: usize| {
+            self.softirqs
+                .iter()
+                .find(|s| s.name == name)
+                .and_then(|s| s.rates.get(index))
+                .copied()
+                .unwrap_or(0.0)
+        };
+
+        self.net_saturated_cpus = cpu_stats
+            .cores
+            .iter()
+            .filter_map(|core| {
+                let cpu: usize = core.cpu.strip_prefix("cpu")?.parse().ok()?;
+                let index = cpu_index(&self.softirq_cpus, cpu)?;
+                let softirq_pct = core.breakdown.as_ref()?.softirq;
+                let net = rate("NET_RX", index) + rate("NET_TX", index);
+                let total: f64 = self.softirqs.iter().filter_map(|s| s.rates.get(index)).sum();
+                (softirq_pct >= NET_SOFTIRQ_SATURATION_PCT && total > 0.0 && net / total >= 0.5).then_some(cpu)
+            })
+            .collect();
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn collect_interrupts(root: &FsRoot) -> anyhow::Result<Option<InterruptStats>> {
+    let (irq_cpus, interrupts) = parse_interrupts(&root.require_proc("interrupts")?);
+    // Only present since 2.6.31
+    let (softirq_cpus, softirqs) = root.optional_proc("softirqs")?
+        .map(|data| parse_softirqs(&data))
+        .unwrap_or_default();
+
+    Ok(Some(InterruptStats { interrupts, softirqs, irq_cpus, softirq_cpus, ..Default::default() }))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn collect_interrupts(_root: &FsRoot) -> anyhow::Result<Option<InterruptStats>> {
+    Ok(None)
+}
+
+// CPU ids from a header such as "       CPU0       CPU2       CPU3"
+fn parse_cpu_header(header: &str) -> Vec<usize> {
+    header
+        .split_whitespace()
+        .filter_map(|c| c.strip_prefix("CPU")?.parse().ok())
+        .collect()
+}
+
+/// Parse the contents of /proc/interrupts into the CPU ids from the header and the
+/// interrupts, whose counts follow the header's order.
+pub fn parse_interrupts(data: &str) -> (Vec<usize>, Vec<IrqInfo>) {
+    let mut lines = data.lines();
+    // Offline CPUs are left out of the header and the counts
+    let Some(cpus) = lines.next().map(parse_cpu_header) else {
+        return (Vec::new(), Vec::new());
+    };
+    let cpu_count = cpus.len();
+
+    let interrupts = lines
+        .filter_map(|line| {
+            let (irq, rest) = line.split_once(':')?;
+            let mut fields = rest.split_whitespace().peekable();
+            // Lines such as ERR and MIS carry a single system-wide count
+            let mut counts = Vec::with_capacity(cpu_count);
+            while counts.len() < cpu_count {
+                match fields.peek().and_then(|f| f.parse::<u64>().ok()) {
+                    Some(count) => {
+                        counts.push(count);
+                        fields.next();
+                    }
+                    None => break,
+                }
+            }
+            let description: Vec<&str> = fields.collect();
+
+            // Numbered IRQs describe the chip and hwirq/trigger ("IO-APIC 5-edge") before
+            // the handlers; named ones ("LOC", "NMI") only carry a description
+            let trigger = description.iter().position(|f| {
+                ["-edge", "-level", "-fasteoi", "Edge", "Level"].iter().any(|t| f.ends_with(t))
+            });
+            let (controller, device) = match trigger {
+                Some(pos) if irq.trim().parse::<u32>().is_ok() => {
+                    (description[..=pos].join(" "), description[pos + 1..].join(" "))
+                }
+                _ => (String::new(), description.join(" ")),
+            };
+
+            Some(IrqInfo {
+                irq: irq.trim().to_string(),
+                controller,
+                device,
+                counts,
+                ..Default::default()
+            })
+        })
+        .collect();
+    (cpus, interrupts)
+}
+
+/// Parse the contents of /proc/softirqs into the CPU ids from the header and the softirqs.
+pub fn parse_softirqs(data: &str) -> (Vec<usize>, Vec<SoftirqInfo>) {
+    let mut lines = data.lines();
+    let cpus = lines.next().map(parse_cpu_header).unwrap_or_default();
+    let softirqs = lines
+        .filter_map(|line| {
+            let (name, counts) = line.split_once(':')?;
+            Some(SoftirqInfo {
+                name: name.trim().to_string(),
+                counts: counts.split_whitespace().filter_map(|v| v.parse().ok()).collect(),
+                rates: Vec::new(),
+            })
+        })
+        .collect();
+    (cpus, softirqs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::cpu_mem::{CpuBreakdown, CpuCoreStats};
+
+    #[test]
+    fn test_parse_interrupts() {
+        let data = "\
+           CPU0       CPU1
+  24:          1          0  IO-APIC    5-edge      ACPI:Ged
+ 130:     912345         12  IR-PCI-MSI 524288-edge      eth0-TxRx-0
+ LOC:    4567890    4567001   Local timer interrupts
+ ERR:          0
+";
+        let (cpus, irqs) = parse_interrupts(data);
+        assert_eq!(cpus, vec![0, 1]);
+        assert_eq!(irqs.len(), 4);
+        assert_eq!(irqs[1].irq, "130");
+        assert_eq!(irqs[1].controller, "IR-PCI-MSI 524288-edge");
+        assert_eq!(irqs[1].device, "eth0-TxRx-0");
+        assert_eq!(irqs[1].counts, vec![912345, 12]);
+        assert_eq!(irqs[2].device, "Local timer interrupts");
+        assert_eq!(irqs[3].counts, vec![0]);
+    }
+
+    #[test]
+    fn test_flag_net_saturated_cpu() {
+        let softirqs = "\
+                    CPU0       CPU1
+      TIMER:       1000       1000
+     NET_RX:       1000     500000
+";
+        let (softirq_cpus, parsed) = parse_softirqs(softirqs);
+        let previous = InterruptStats { softirqs: parsed, softirq_cpus, ..Default::default() };
+        let later = softirqs.replace("     500000", "    1500000").replace("1000       1000", "1100       1100");
+        let (softirq_cpus, parsed) = parse_softirqs(&later);
+        let mut stats = InterruptStats { softirqs: parsed, softirq_cpus, ..Default::default() }
+            .with_rates(&previous, 1000.0);
+
+        let core = |cpu: &str, softirq: f64| CpuCoreStats {
+            cpu: cpu.to_string(),
+            breakdown: Some(CpuBreakdown { softirq, ..Default::default() }),
+            ..Default::default()
+        };
+        let cpu_stats = CpuStats { cores: vec![core("cpu0", 45.0), core("cpu1", 45.0)], ..Default::default() };
+        stats.flag_net_saturation(Some(&cpu_stats));
+
+        // CPU0 is busy in softirq too, but on timers rather than packets
+        assert_eq!(stats.net_saturated_cpus, vec![1]);
+    }
+
+    #[test]
+    fn test_offline_cpu_gap() {
+        // CPU1 is offline, so the second column belongs to CPU2
+        let interrupts = "\
+           CPU0       CPU2       CPU3
+ 130:        100        200        300  IR-PCI-MSI 524288-edge      eth0-TxRx-0
+";
+        let softirqs = "\
+                    CPU0       CPU2       CPU3
+      TIMER:       1000       1000       1000
+     NET_RX:       1000       1000       1000
+";
+        let parse = |interrupts: &str, softirqs: &str| {
+            let (irq_cpus, interrupts) = parse_interrupts(interrupts);
+            let (softirq_cpus, softirqs) = parse_softirqs(softirqs);
+            InterruptStats { interrupts, softirqs, irq_cpus, softirq_cpus, ..Default::default() }
+        };
+        let previous = parse(interrupts, softirqs);
+        assert_eq!(previous.irq_cpus, vec![0, 2, 3]);
+        assert_eq!(previous.softirq_cpus, vec![0, 2, 3]);
+
+        // CPU3 goes offline as well; CPU2's counters must still line up with CPU2's
+        let interrupts = "\
+           CPU0       CPU2
+ 130:        100        700  IR-PCI-MSI 524288-edge      eth0-TxRx-0
+";
+        let softirqs = "\
+                    CPU0       CPU2
+      TIMER:       1000       1010
+     NET_RX:       1000     501000
+";
+        let mut stats = parse(interrupts, softirqs).with_rates(&previous, 1000.0);
+        assert_eq!(stats.interrupts[0].rates, vec![0.0, 500.0]);
+        assert_eq!(stats.softirqs[1].rates, vec![0.0, 500000.0]);
+
+        let core = |cpu: &str| CpuCoreStats {
+            cpu: cpu.to_string(),
+            breakdown: Some(CpuBreakdown { softirq: 45.0, ..Default::default() }),
+            ..Default::default()
+        };
+        let cpu_stats = CpuStats { cores: vec![core("cpu0"), core("cpu2")], ..Default::default() };
+        stats.flag_net_saturation(Some(&cpu_stats));
+        assert_eq!(stats.net_saturated_cpus, vec![2]);
+    }
+}
@@ -1,11 +1,21 @@
 pub mod snapshot;
+pub mod registry;
+pub mod fsroot;
+mod builtin;
 pub mod system;
 pub mod cpu_mem;
+pub mod vmstat;
+pub mod numa_topology;
+pub mod interrupts;
 pub mod load;
+pub mod psi;
 pub mod net;
+pub mod tcp;
 pub mod disk;
+pub mod storage_health;
 pub mod process;
 pub mod sensors;
+pub mod rapl;
 pub mod gpu;
 pub mod connections;
 pub mod process_tree;
@@ -1,7 +1,8 @@
 use serde::{Serialize, Deserialize};
+use super::fsroot::FsRoot;
 use sysinfo::NetworkData;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct NetworkInfo {
     pub interface: String,
     pub rx_bytes: u64,
@@ -10,13 +11,54 @@ pub struct NetworkInfo {
     pub tx_packets: u64,
     pub rx_errors: u64,
     pub tx_errors: u64,
+    #[serde(default)]
+    pub rx_dropped: u64,
+    #[serde(default)]
+    pub tx_dropped: u64,
+    #[serde(default)]
+    pub multicast: u64,   // Multicast packets received
+    // Link metadata (Linux)
+    #[serde(default)]
+    pub kind: InterfaceKind,
+    #[serde(default)]
+    pub operstate: String,   // "up", "down", "dormant", ... or "unknown"
+    #[serde(default)]
+    pub speed_mbps: Option<u64>,   // None for virtual links and links that are down
+    #[serde(default)]
+    pub duplex: Option<String>,
+    #[serde(default)]
+    pub mtu: Option<u32>,
+    #[serde(default)]
+    pub mac_address: Option<String>,
+    #[serde(default)]
+    pub ip_addresses: Vec<String>,   // CIDR notation, e.g. "192.168.1.10/24"
     // Throughput data (bytes per second)
     pub rx_throughput: f64,
     pub tx_throughput: f64,
+    #[serde(default)]
+    pub rx_packet_rate: f64,
+    #[serde(default)]
+    pub tx_packet_rate: f64,
+    // Busier direction as a share of link speed (both directions combined on half duplex)
+    #[serde(default)]
+    pub utilization_percent: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InterfaceKind {
+    Physical,
+    Virtual,
+    Bridge,
+    Veth,
+    Loopback,
+    #[default]
+    Unknown,
 }
 
 impl NetworkInfo {
     pub fn from_network_data(name: &str, data: &NetworkData) -> Self {
+        let mac = data.mac_address();
         Self {
             interface: name.to_string(),
             rx_bytes: data.total_received(),
@@ -25,22 +67,269 @@ impl NetworkInfo {
             tx_packets: data.total_packets_transmitted(),
             rx_errors: data.total_errors_on_received(),
             tx_errors: data.total_errors_on_transmitted(),
+            rx_dropped: 0,
+            tx_dropped: 0,
+            multicast: 0,
+            kind: InterfaceKind::Unknown,
+            operstate: "unknown".to_string(),
+            speed_mbps: None,
+            duplex: None,
+            mtu: None,
+            mac_address: (!mac.is_unspecified()).then(|| mac.to_string()),
+            ip_addresses: Vec::new(),
             // Initialize throughput to 0, will be calculated when comparing with previous snapshots
             rx_throughput: 0.0,
             tx_throughput: 0.0,
+            rx_packet_rate: 0.0,
+            tx_packet_rate: 0.0,
+            utilization_percent: None,
         }
     }
-    
+
     // Calculate throughput based on previous snapshot
     pub fn with_throughput(mut self, previous: &NetworkInfo, time_delta_ms: f64) -> Self {
+        if time_delta_ms <= 0.0 {
+            return self;
+        }
+        let secs = time_delta_ms / 1000.0;
+
         // Calculate bytes per second
         let rx_delta = self.rx_bytes.saturating_sub(previous.rx_bytes);
         let tx_delta = self.tx_bytes.saturating_sub(previous.tx_bytes);
-        
+
         // Convert to bytes per second (time_delta is in milliseconds)
-        self.rx_throughput = (rx_delta as f64) / (time_delta_ms / 1000.0);
-        self.tx_throughput = (tx_delta as f64) / (time_delta_ms / 1000.0);
-        
+        self.rx_throughput = (rx_delta as f64) / secs;
+        self.tx_throughput = (tx_delta as f64) / secs;
+        self.rx_packet_rate = self.rx_packets.saturating_sub(previous.rx_packets) as f64 / secs;
+        self.tx_packet_rate = self.tx_packets.saturating_sub(previous.tx_packets) as f64 / secs;
+
+        if let Some(speed) = self.speed_mbps.filter(|s| *s > 0) {
+            let capacity = speed as f64 * 1_000_000.0 / 8.0;
+            let used = if self.duplex.as_deref() == Some("half") {
+                self.rx_throughput + self.tx_throughput
+            } else {
+                self.rx_throughput.max(self.tx_throughput)
+            };
+            self.utilization_percent = Some((used / capacity * 100.0).min(100.0));
+        }
+
         self
     }
-}
\ No newline at end of file
+}
+
+/// Fill in drops, multicast and link metadata from /proc/net/dev and /sys/class/net.
+#[cfg(target_os = "linux")]
+pub fn attach_interface_details(root: &FsRoot, networks: &mut [NetworkInfo]) {
+    use std::fs;
+
+    let dev_stats = root.read_proc("net/dev")
+        .map(|data| parse_proc_net_dev(&data))
+        .unwrap_or_default();
+    // getifaddrs only sees this process's network namespace, which a capture isn't
+    let addresses = if *root == FsRoot::default() { interface_addresses() } else { Vec::new() };
+
+    for net in networks.iter_mut() {
+        if let Some(stats) = dev_stats.iter().find(|s| s.interface == net.interface) {
+            net.rx_dropped = stats.rx_dropped;
+            net.tx_dropped = stats.tx_dropped;
+            net.multicast = stats.multicast;
+        }
+        net.ip_addresses = addresses
+            .iter()
+            .filter(|(name, _)| *name == net.interface)
+            .map(|(_, addr)| addr.clone())
+            .collect();
+
+        let dir = root.sys("class/net").join(&net.interface);
+        let read = |file: &str| fs::read_to_string(dir.join(file)).ok().map(|v| v.trim().to_string());
+
+        net.kind = classify_interface(&net.interface, &dir);
+        net.operstate = read("operstate").unwrap_or_else(|| "unknown".to_string());
+        net.mtu = read("mtu").and_then(|v| v.parse().ok());
+        // speed reads as -1 (or fails with EINVAL) when the link is down or has no fixed rate
+        net.speed_mbps = read("speed").and_then(|v| v.parse::<i64>().ok()).filter(|s| *s > 0).map(|s| s as u64);
+        net.duplex = read("duplex").filter(|d| d == "full" || d == "half");
+        if let Some(mac) = read("address").filter(|a| !a.is_empty() && a != "00:00:00:00:00:00") {
+            net.mac_address = Some(mac);
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn attach_interface_details(_root: &FsRoot, _networks: &mut [NetworkInfo]) {}
+
+#[cfg(target_os = "linux")]
+fn classify_interface(name: &str, dir: &std::path::Path) -> InterfaceKind {
+    use std::fs;
+
+    let read = |file: &str| fs::read_to_string(dir.join(file)).ok().map(|v| v.trim().to_string());
+    let devtype = read("uevent").and_then(|uevent| {
+        uevent
+            .lines()
+            .find_map(|l| l.strip_prefix("DEVTYPE=").map(String::from))
+    });
+
+    // ARPHRD_LOOPBACK
+    if read("type").as_deref() == Some("772") {
+        InterfaceKind::Loopback
+    } else if dir.join("bridge").exists() || devtype.as_deref() == Some("bridge") {
+        InterfaceKind::Bridge
+    } else if dir.join("device").exists() {
+        // Backed by a bus device (PCI, USB, virtio, ...)
+        InterfaceKind::Physical
+    } else if read("type").as_deref() == Some("1")
+        && (name.starts_with("veth")
+            // Container runtimes and CNI plugins name their veth ends differently; the common
+            // tell is a peer link (iflink != ifindex) with no more specific DEVTYPE. Tunnels
+            // have a lower link too, but aren't ARPHRD_ETHER
+            || (devtype.is_none() && read("iflink").is_some() && read("iflink") != read("ifindex")))
+    {
+        InterfaceKind::Veth
+    } else {
+        InterfaceKind::Virtual
+    }
+}
+
+// IPv4 and IPv6 addresses per interface, as (interface, "address/prefix")
+#[cfg(target_os = "linux")]
+fn interface_addresses() -> Vec<(String, String)> {
+    let Ok(addrs) = nix::ifaddrs::getifaddrs() else {
+        return Vec::new();
+    };
+    addrs
+        .filter_map(|ifaddr| {
+            let address = ifaddr.address?;
+            let netmask = ifaddr.netmask;
+            let formatted = if let Some(v4) = address.as_sockaddr_in() {
+                let prefix = netmask
+                    .as_ref()
+                    .and_then(|m| m.as_sockaddr_in())
+                    .map_or(32, |m| m.ip().to_bits().count_ones());
+                format!("{}/{}", v4.ip(), prefix)
+            } else if let Some(v6) = address.as_sockaddr_in6() {
+                let prefix = netmask
+                    .as_ref()
+                    .and_then(|m| m.as_sockaddr_in6())
+                    .map_or(128, |m| m.ip().to_bits().count_ones());
+                format!("{}/{}", v6.ip(), prefix)
+            } else {
+                return None;
+            };
+            Some((ifaddr.interface_name, formatted))
+        })
+        .collect()
+}
+
+/// Drop and multicast counters for one interface from /proc/net/dev.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DevStats {
+    pub interface: String,
+    pub rx_dropped: u64,
+    pub tx_dropped: u64,
+    pub multicast: u64,
+}
+
+// After the two header lines:
+//   "  eth0: rx_bytes packets errs drop fifo frame compressed multicast tx_bytes packets errs drop ..."
+pub fn parse_proc_net_dev(data: &str) -> Vec<DevStats> {
+    data.lines()
+        .skip(2)
+        .filter_map(|line| {
+            let (name, counters) = line.split_once(':')?;
+            let counters: Vec<u64> = counters
+                .split_whitespace()
+                .map(|v| v.parse().unwrap_or(0))
+                .collect();
+            if counters.len() < 16 {
+                return None;
+            }
+            Some(DevStats {
+                interface: name.trim().to_string(),
+                rx_dropped: counters[3],
+                multicast: counters[7],
+                tx_dropped: counters[11],
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_proc_net_dev() {
+        let data = "\
+Inter-|   Receive                                                |  Transmit
+ face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
+    lo: 33945784    3927    0    0    0     0          0         0 33945784    3927    0    0    0     0       0          0
+  eth0: 1000 10 0 4 0 0 0 7 2000 20 0 2 0 0 0 0
+";
+        let stats = parse_proc_net_dev(data);
+        assert_eq!(stats.len(), 2);
+        assert_eq!(
+            stats[1],
+            DevStats { interface: "eth0".to_string(), rx_dropped: 4, tx_dropped: 2, multicast: 7 }
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_classify_interface() {
+        let dir = std::env::temp_dir().join(format!("zek-netclass-{}", std::process::id()));
+        let iface = |name: &str, files: &[(&str, &str)]| {
+            let path = dir.join(name);
+            std::fs::create_dir_all(&path).unwrap();
+            for (file, value) in files {
+                std::fs::write(path.join(file), value).unwrap();
+            }
+            classify_interface(name, &path)
+        };
+
+        let kinds = [
+            iface("lo", &[("type", "772\n")]),
+            iface("eth1", &[("type", "1\n"), ("iflink", "12\n"), ("ifindex", "9\n")]),
+            iface("veth3f2a", &[("type", "1\n"), ("iflink", "4\n"), ("ifindex", "4\n")]),
+            // An ipip tunnel over eth0
+            iface("tun0", &[("type", "768\n"), ("iflink", "2\n"), ("ifindex", "7\n")]),
+            iface("dummy0", &[("type", "1\n"), ("iflink", "5\n"), ("ifindex", "5\n")]),
+        ];
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(
+            kinds,
+            [
+                InterfaceKind::Loopback,
+                InterfaceKind::Veth,
+                InterfaceKind::Veth,
+                InterfaceKind::Virtual,
+                InterfaceKind::Virtual,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_utilization_against_link_speed() {
+        let mut previous = NetworkInfo {
+            interface: "eth0".to_string(),
+            kind: InterfaceKind::Physical,
+            operstate: "up".to_string(),
+            speed_mbps: Some(1000),
+            duplex: Some("full".to_string()),
+            ..Default::default()
+        };
+        // 62.5 MB/s received is half of a gigabit link
+        let mut current = previous.clone();
+        current.rx_bytes = 62_500_000;
+        current.tx_bytes = 1_000_000;
+        current.rx_packets = 1000;
+        let current = current.clone().with_throughput(&previous, 1000.0);
+        assert_eq!(current.utilization_percent, Some(50.0));
+        assert_eq!(current.rx_packet_rate, 1000.0);
+
+        previous.duplex = Some("half".to_string());
+        let mut half = previous.clone();
+        half.rx_bytes = 62_500_000;
+        half.tx_bytes = 62_500_000;
+        assert_eq!(half.with_throughput(&previous, 1000.0).utilization_percent, Some(100.0));
+    }
+}
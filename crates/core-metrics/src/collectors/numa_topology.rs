@@ -0,0 +1,218 @@
+use serde::{Serialize, Deserialize};
+use std::path::Path;
+use super::fsroot::{read_dir_optional, FsRoot};
+
+/// NUMA nodes and logical CPU placement from /sys/devices/system/{node,cpu}.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NumaTopology {
+    pub nodes: Vec<NumaNode>,
+    pub cpus: Vec<CpuTopology>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NumaNode {
+    pub id: usize,
+    pub cpus: Vec<usize>,
+    pub mem_total_bytes: u64,
+    pub mem_free_bytes: u64,
+    pub mem_used_bytes: u64,
+    // Cumulative allocation counters from numastat
+    pub numa_hit: u64,
+    pub numa_miss: u64,      // Allocated here although another node was preferred
+    pub numa_foreign: u64,   // Meant for this node but allocated elsewhere
+    pub interleave_hit: u64,
+    pub local_node: u64,
+    pub other_node: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CpuTopology {
+    pub cpu: usize,
+    pub socket: Option<u32>,   // physical_package_id
+    pub core_id: Option<u32>,
+    pub node: Option<usize>,
+    pub thread_siblings: Vec<usize>,   // SMT siblings, including this CPU
+}
+
+/// (socket, node) pair used to group logical CPUs.
+pub type CpuGroupKey = (Option<u32>, Option<usize>);
+
+impl NumaTopology {
+    // Logical CPUs grouped by (socket, node), in CPU order within each group
+    pub fn cpu_groups(&self) -> Vec<(CpuGroupKey, Vec<usize>)> {
+        let mut groups: Vec<(CpuGroupKey, Vec<usize>)> = Vec::new();
+        for cpu in &self.cpus {
+            let key = (cpu.socket, cpu.node);
+            match groups.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, cpus)) => cpus.push(cpu.cpu),
+                None => groups.push((key, vec![cpu.cpu])),
+            }
+        }
+        groups.sort_by_key(|(key, _)| *key);
+        groups
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn collect_numa_topology(root: &FsRoot) -> anyhow::Result<Option<NumaTopology>> {
+    collect_numa_topology_from(&root.sys("devices/system"))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn collect_numa_topology(_root: &FsRoot) -> anyhow::Result<Option<NumaTopology>> {
+    Ok(None)
+}
+
+/// Read node and CPU topology under a /sys/devices/system style directory.
+pub fn collect_numa_topology_from(root: &Path) -> anyhow::Result<Option<NumaTopology>> {
+    use std::fs;
+
+    let read = |path: &Path| fs::read_to_string(path).ok().map(|v| v.trim().to_string());
+    let numbered = |dir: &Path, prefix: &str| -> anyhow::Result<Vec<usize>> {
+        let mut ids: Vec<usize> = read_dir_optional(dir)?
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter_map(|e| e.file_name().to_str()?.strip_prefix(prefix)?.parse().ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+        ids.sort_unstable();
+        Ok(ids)
+    };
+
+    // Kernels without CONFIG_NUMA have no node directory; CPU topology is still useful
+    let mut nodes = Vec::new();
+    for id in numbered(&root.join("node"), "node")? {
+        let dir = root.join("node").join(format!("node{}", id));
+        let mut node = NumaNode {
+            id,
+            cpus: read(&dir.join("cpulist")).map(|l| parse_cpu_list(&l)).unwrap_or_default(),
+            ..Default::default()
+        };
+        if let Some(meminfo) = read(&dir.join("meminfo")) {
+            // "Node 0 MemTotal:        6158152 kB"
+            for line in meminfo.lines() {
+                let mut fields = line.split_whitespace().skip(2);
+                let (Some(key), Some(value)) = (fields.next(), fields.next()) else {
+                    continue;
+                };
+                let bytes = value.parse::<u64>().unwrap_or(0) * 1024;
+                match key {
+                    "MemTotal:" => node.mem_total_bytes = bytes,
+                    "MemFree:" => node.mem_free_bytes = bytes,
+                    "MemUsed:" => node.mem_used_bytes = bytes,
+                    _ => {}
+                }
+            }
+        }
+        if let Some(numastat) = read(&dir.join("numastat")) {
+            for line in numastat.lines() {
+                let Some((key, value)) = line.split_once(' ') else {
+                    continue;
+                };
+                let value = value.trim().parse().unwrap_or(0);
+                match key {
+                    "numa_hit" => node.numa_hit = value,
+                    "numa_miss" => node.numa_miss = value,
+                    "numa_foreign" => node.numa_foreign = value,
+                    "interleave_hit" => node.interleave_hit = value,
+                    "local_node" => node.local_node = value,
+                    "other_node" => node.other_node = value,
+                    _ => {}
+                }
+            }
+        }
+        nodes.push(node);
+    }
+
+    let cpus: Vec<CpuTopology> = numbered(&root.join("cpu"), "cpu")?
+        .into_iter()
+        .map(|cpu| {
+            let dir = root.join("cpu").join(format!("cpu{}", cpu)).join("topology");
+            // Offline CPUs report -1 for their ids
+            let id = |file: &str| read(&dir.join(file)).and_then(|v| v.parse::<u32>().ok());
+            CpuTopology {
+                cpu,
+                socket: id("physical_package_id"),
+                core_id: id("core_id"),
+                node: nodes.iter().find(|n| n.cpus.contains(&cpu)).map(|n| n.id),
+                thread_siblings: read(&dir.join("thread_siblings_list"))
+                    .map(|l| parse_cpu_list(&l))
+                    .unwrap_or_default(),
+            }
+        })
+        .collect();
+
+    if nodes.is_empty() && cpus.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(NumaTopology { nodes, cpus }))
+    }
+}
+
+/// Parse a kernel CPU list such as "0-3,8-11,16".
+pub fn parse_cpu_list(list: &str) -> Vec<usize> {
+    list.split(',')
+        .filter_map(|part| {
+            let part = part.trim();
+            match part.split_once('-') {
+                Some((start, end)) => Some((start.parse().ok()?..=end.parse().ok()?).collect::<Vec<usize>>()),
+                None => Some(vec![part.parse().ok()?]),
+            }
+        })
+        .flatten()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_parse_cpu_list() {
+        assert_eq!(parse_cpu_list("0-3,8-9,12"), vec![0, 1, 2, 3, 8, 9, 12]);
+        assert_eq!(parse_cpu_list("5"), vec![5]);
+        assert!(parse_cpu_list("").is_empty());
+    }
+
+    #[test]
+    fn test_two_socket_topology() {
+        let root = std::env::temp_dir().join(format!("zek-numa-two-socket-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+
+        for (node, cpus) in [(0, "0,2"), (1, "1,3")] {
+            let dir = root.join(format!("node/node{}", node));
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("cpulist"), cpus).unwrap();
+            fs::write(
+                dir.join("meminfo"),
+                format!("Node {0} MemTotal:  1024 kB\nNode {0} MemFree:  256 kB\nNode {0} MemUsed:  768 kB\n", node),
+            )
+            .unwrap();
+            fs::write(dir.join("numastat"), "numa_hit 100\nnuma_miss 7\nnuma_foreign 3\n").unwrap();
+        }
+        // Two sockets with two SMT threads on one core each; socket follows cpu parity
+        for cpu in 0..4 {
+            let dir = root.join(format!("cpu/cpu{}/topology", cpu));
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("physical_package_id"), format!("{}\n", cpu % 2)).unwrap();
+            fs::write(dir.join("core_id"), "0\n").unwrap();
+            fs::write(dir.join("thread_siblings_list"), if cpu % 2 == 0 { "0,2\n" } else { "1,3\n" }).unwrap();
+        }
+
+        let topology = collect_numa_topology_from(&root).unwrap().unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(topology.nodes.len(), 2);
+        assert_eq!(topology.nodes[1].mem_used_bytes, 768 * 1024);
+        assert_eq!(topology.nodes[0].numa_miss, 7);
+        assert_eq!(topology.cpus[3].node, Some(1));
+        assert_eq!(topology.cpus[2].thread_siblings, vec![0, 2]);
+        assert_eq!(
+            topology.cpu_groups(),
+            vec![((Some(0), Some(0)), vec![0, 2]), ((Some(1), Some(1)), vec![1, 3])]
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use serde::{Serialize, Deserialize};
 use sysinfo::Process;
+use super::fsroot::FsRoot;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessInfo {
@@ -8,16 +9,480 @@ pub struct ProcessInfo {
     pub cpu_usage: f32,
     pub memory: u64,
     pub status: String,
+    #[serde(default)]
+    pub threads: u64,
+    #[serde(default)]
+    pub start_time: u64,   // Unix timestamp in seconds
+    // Disk I/O since the previous refresh
+    #[serde(default)]
+    pub disk_read_bytes: u64,
+    #[serde(default)]
+    pub disk_write_bytes: u64,
+    // Only counted when sorting by open fds
+    #[serde(default)]
+    pub open_fds: Option<u64>,
+    // Cumulative user+system CPU time; only read in all-processes mode
+    #[serde(default)]
+    pub cpu_time_ms: u64,
 }
 
 impl ProcessInfo {
     pub fn from_process(pid: u32, process: &Process) -> Self {
+        let disk_usage = process.disk_usage();
         Self {
             pid,
             name: process.name().to_string(),
             cpu_usage: process.cpu_usage(),
             memory: process.memory(),
             status: format!("{:?}", process.status()),
+            // Threads show up as tasks on Linux; other platforms count the process itself
+            threads: process.tasks().map_or(1, |tasks| tasks.len().max(1) as u64),
+            start_time: process.start_time(),
+            disk_read_bytes: disk_usage.read_bytes,
+            disk_write_bytes: disk_usage.written_bytes,
+            open_fds: None,
+            cpu_time_ms: 0,
         }
     }
-}
\ No newline at end of file
+    
+    // Calculate CPU usage from cumulative CPU time based on previous snapshot
+    pub fn with_cpu_delta(mut self, previous_cpu_time_ms: u64, time_delta_ms: f64) -> Self {
+        if time_delta_ms > 0.0 {
+            let cpu_delta = self.cpu_time_ms.saturating_sub(previous_cpu_time_ms);
+            self.cpu_usage = (cpu_delta as f64 / time_delta_ms * 100.0) as f32;
+        }
+        self
+    }
+}
+
+/// One row of the all-processes table. Every process is carried on every tick in that
+/// mode, so this keeps only what's needed to spot a resource hog and to compute the
+/// next tick's CPU delta.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessRow {
+    pub pid: u32,
+    pub name: String,
+    pub cpu_usage: f32,
+    pub memory: u64,   // Resident set size, bytes
+    pub disk_read_bytes: u64,
+    pub disk_write_bytes: u64,
+    pub start_time: u64,
+    pub cpu_time_ms: u64,
+}
+
+impl From<&ProcessInfo> for ProcessRow {
+    fn from(process: &ProcessInfo) -> Self {
+        Self {
+            pid: process.pid,
+            name: process.name.clone(),
+            cpu_usage: process.cpu_usage,
+            memory: process.memory,
+            disk_read_bytes: process.disk_read_bytes,
+            disk_write_bytes: process.disk_write_bytes,
+            start_time: process.start_time,
+            cpu_time_ms: process.cpu_time_ms,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessSortKey {
+    #[default]
+    Cpu,
+    Memory,
+    Io,
+    Fds,
+    Threads,
+    StartTime,
+}
+
+/// Process table settings from the `[processes]` section of zek.toml.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ProcessOptions {
+    pub top_n: usize,
+    pub sort_by: ProcessSortKey,
+    // Carry every process in the snapshot, not just the top N
+    pub all: bool,
+}
+
+impl Default for ProcessOptions {
+    fn default() -> Self {
+        Self {
+            top_n: 10,
+            sort_by: ProcessSortKey::Cpu,
+            all: false,
+        }
+    }
+}
+
+// Sort descending by the given key (newest first for start time)
+pub fn sort_processes(processes: &mut [ProcessInfo], key: ProcessSortKey) {
+    match key {
+        ProcessSortKey::Cpu => processes.sort_by(|a, b| {
+            b.cpu_usage.partial_cmp(&a.cpu_usage).unwrap_or(std::cmp::Ordering::Equal)
+        }),
+        ProcessSortKey::Memory => processes.sort_by_key(|p| std::cmp::Reverse(p.memory)),
+        ProcessSortKey::Io => processes.sort_by_key(|p| std::cmp::Reverse(p.disk_read_bytes + p.disk_write_bytes)),
+        ProcessSortKey::Fds => processes.sort_by_key(|p| std::cmp::Reverse(p.open_fds.unwrap_or(0))),
+        ProcessSortKey::Threads => processes.sort_by_key(|p| std::cmp::Reverse(p.threads)),
+        ProcessSortKey::StartTime => processes.sort_by_key(|p| std::cmp::Reverse(p.start_time)),
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn count_open_fds(root: &FsRoot, pid: u32) -> Option<u64> {
+    std::fs::read_dir(root.proc(&format!("{}/fd", pid)))
+        .ok()
+        .map(|entries| entries.count() as u64)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn count_open_fds(_root: &FsRoot, _pid: u32) -> Option<u64> {
+    None
+}
+
+// utime + stime from /proc/<pid>/stat
+#[cfg(target_os = "linux")]
+pub fn read_cpu_time_ms(root: &FsRoot, pid: u32) -> Option<u64> {
+    let stat = root.read_proc(&format!("{}/stat", pid)).ok()?;
+    let fields: Vec<&str> = stat[stat.rfind(')')? + 1..].split_whitespace().collect();
+    let utime: u64 = fields.get(11)?.parse().ok()?;
+    let stime: u64 = fields.get(12)?.parse().ok()?;
+    Some((utime + stime) * 1000 / CLOCK_TICKS_PER_SEC)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn read_cpu_time_ms(_root: &FsRoot, _pid: u32) -> Option<u64> {
+    None
+}
+
+/// Detailed view of a single process. Gathering these fields means reading several
+/// /proc files per process, so they're only collected on demand for the processes
+/// being inspected rather than on every tick.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProcessDetails {
+    pub pid: u32,
+    pub parent_pid: Option<u32>,
+    pub name: String,
+    pub cmdline: Vec<String>,
+    pub state: String,
+    pub uid: Option<u32>,
+    pub user: Option<String>,
+    pub start_time: i64,   // Unix timestamp in seconds
+    pub threads: u64,
+    pub nice: i64,
+    pub priority: i64,
+    pub open_fds: Option<u64>,
+    pub cpu_user_ms: u64,
+    pub cpu_system_ms: u64,
+    pub minor_faults: u64,
+    pub major_faults: u64,
+    pub voluntary_ctx_switches: u64,
+    pub involuntary_ctx_switches: u64,
+    // From smaps_rollup and io; None when we lack permission to read them
+    pub rss_bytes: Option<u64>,
+    pub pss_bytes: Option<u64>,
+    pub swap_bytes: Option<u64>,
+    pub io_read_bytes: Option<u64>,
+    pub io_write_bytes: Option<u64>,
+}
+
+// USER_HZ, the unit of the time fields in /proc/<pid>/stat. Fixed at 100 on every
+// mainstream Linux architecture regardless of the kernel's internal HZ.
+#[cfg(target_os = "linux")]
+const CLOCK_TICKS_PER_SEC: u64 = 100;
+
+#[cfg(target_os = "linux")]
+pub fn collect_process_details(root: &FsRoot, pid: u32) -> Option<ProcessDetails> {
+    use std::fs;
+    
+    let proc_dir = root.proc(&pid.to_string());
+    let stat = fs::read_to_string(proc_dir.join("stat")).ok()?;
+    let mut details = parse_stat(pid, &stat, boot_time(root))?;
+    
+    // Arguments are NUL-separated; kernel threads have an empty cmdline
+    if let Ok(cmdline) = fs::read(proc_dir.join("cmdline")) {
+        details.cmdline = cmdline
+            .split(|b| *b == 0)
+            .filter(|arg| !arg.is_empty())
+            .map(|arg| String::from_utf8_lossy(arg).to_string())
+            .collect();
+    }
+    
+    if let Ok(status) = fs::read_to_string(proc_dir.join("status")) {
+        parse_status(&mut details, &status);
+    }
+    details.user = details.uid.and_then(user_name);
+    
+    details.open_fds = count_open_fds(root, pid);
+    
+    if let Ok(rollup) = fs::read_to_string(proc_dir.join("smaps_rollup")) {
+        parse_smaps_rollup(&mut details, &rollup);
+    }
+    
+    if let Ok(io) = fs::read_to_string(proc_dir.join("io")) {
+        parse_io(&mut details, &io);
+    }
+    
+    Some(details)
+}
+
+// /proc/<pid>/stat, with the start time resolved against the boot time when known
+#[cfg(target_os = "linux")]
+fn parse_stat(pid: u32, stat: &str, boot_time: Option<i64>) -> Option<ProcessDetails> {
+    // The command name is wrapped in parentheses and may itself contain spaces or ')'
+    let open = stat.find('(')?;
+    let close = stat.rfind(')')?;
+    let name = stat[open + 1..close].to_string();
+    let fields: Vec<&str> = stat[close + 1..].split_whitespace().collect();
+    if fields.len() < 20 {
+        return None;
+    }
+    let field = |i: usize| fields[i].parse::<i64>().unwrap_or(0);
+    
+    let ticks_to_ms = |ticks: i64| ticks.max(0) as u64 * 1000 / CLOCK_TICKS_PER_SEC;
+    let start_time = boot_time.map_or(0, |btime| {
+        btime + (field(19).max(0) as u64 / CLOCK_TICKS_PER_SEC) as i64
+    });
+    
+    Some(ProcessDetails {
+        pid,
+        parent_pid: Some(field(1) as u32).filter(|ppid| *ppid != 0),
+        name,
+        state: fields[0].to_string(),
+        start_time,
+        threads: field(17).max(0) as u64,
+        priority: field(15),
+        nice: field(16),
+        cpu_user_ms: ticks_to_ms(field(11)),
+        cpu_system_ms: ticks_to_ms(field(12)),
+        minor_faults: field(7).max(0) as u64,
+        major_faults: field(9).max(0) as u64,
+        ..Default::default()
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn parse_status(details: &mut ProcessDetails, status: &str) {
+    for line in status.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let first = value.split_whitespace().next().unwrap_or("");
+        match key {
+            // Uid: real effective saved fs
+            "Uid" => details.uid = first.parse().ok(),
+            "voluntary_ctxt_switches" => details.voluntary_ctx_switches = first.parse().unwrap_or(0),
+            "nonvoluntary_ctxt_switches" => details.involuntary_ctx_switches = first.parse().unwrap_or(0),
+            _ => {}
+        }
+    }
+}
+
+// smaps_rollup (Linux 4.14+) reports sizes in kB
+#[cfg(target_os = "linux")]
+fn parse_smaps_rollup(details: &mut ProcessDetails, rollup: &str) {
+    let kb = |key: &str| {
+        rollup.lines()
+            .find_map(|l| l.strip_prefix(key)?.strip_prefix(':'))
+            .and_then(|rest| rest.split_whitespace().next())
+            .and_then(|v| v.parse::<u64>().ok())
+            .map(|v| v * 1024)
+    };
+    details.rss_bytes = kb("Rss");
+    details.pss_bytes = kb("Pss");
+    details.swap_bytes = kb("Swap");
+}
+
+#[cfg(target_os = "linux")]
+fn parse_io(details: &mut ProcessDetails, io: &str) {
+    let value = |key: &str| {
+        io.lines()
+            .find_map(|l| l.strip_prefix(key)?.strip_prefix(':'))
+            .and_then(|rest| rest.trim().parse::<u64>().ok())
+    };
+    details.io_read_bytes = value("read_bytes");
+    details.io_write_bytes = value("write_bytes");
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn collect_process_details(_root: &FsRoot, _pid: u32) -> Option<ProcessDetails> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn boot_time(root: &FsRoot) -> Option<i64> {
+    root.read_proc("stat")
+        .ok()?
+        .lines()
+        .find_map(|l| l.strip_prefix("btime "))
+        .and_then(|v| v.trim().parse().ok())
+}
+
+// Resolve a uid through /etc/passwd; directory-service users (LDAP, sssd) stay unresolved
+#[cfg(target_os = "linux")]
+fn user_name(uid: u32) -> Option<String> {
+    let passwd = std::fs::read_to_string("/etc/passwd").ok()?;
+    passwd.lines().find_map(|line| {
+        let mut fields = line.split(':');
+        let name = fields.next()?;
+        let entry_uid = fields.nth(1)?.parse::<u32>().ok()?;
+        (entry_uid == uid).then(|| name.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: u32, f: impl FnOnce(&mut ProcessInfo)) -> ProcessInfo {
+        let mut process = ProcessInfo {
+            pid,
+            name: format!("proc{}", pid),
+            cpu_usage: 0.0,
+            memory: 0,
+            status: "Sleeping".to_string(),
+            threads: 1,
+            start_time: 0,
+            disk_read_bytes: 0,
+            disk_write_bytes: 0,
+            open_fds: None,
+            cpu_time_ms: 0,
+        };
+        f(&mut process);
+        process
+    }
+
+    #[test]
+    fn test_sort_processes() {
+        let mut processes = vec![
+            process(1, |p| {
+                p.cpu_usage = 5.0;
+                p.memory = 300;
+                p.disk_read_bytes = 10;
+                p.start_time = 100;
+            }),
+            process(2, |p| {
+                p.cpu_usage = 50.0;
+                p.memory = 100;
+                p.threads = 8;
+                p.open_fds = Some(3);
+                p.start_time = 300;
+            }),
+            process(3, |p| {
+                p.cpu_usage = f32::NAN;
+                p.memory = 200;
+                p.disk_write_bytes = 20;
+                p.open_fds = Some(40);
+                p.start_time = 200;
+            }),
+        ];
+        let mut order = |key| {
+            sort_processes(&mut processes, key);
+            processes.iter().map(|p| p.pid).collect::<Vec<_>>()
+        };
+
+        assert_eq!(order(ProcessSortKey::Memory), vec![1, 3, 2]);
+        assert_eq!(order(ProcessSortKey::Io), vec![3, 1, 2]);
+        assert_eq!(order(ProcessSortKey::Fds), vec![3, 2, 1]);
+        assert_eq!(order(ProcessSortKey::Threads)[0], 2);
+        // Newest first
+        assert_eq!(order(ProcessSortKey::StartTime), vec![2, 3, 1]);
+        // A NaN usage doesn't panic the sort
+        let by_cpu = order(ProcessSortKey::Cpu);
+        assert_eq!(by_cpu.len(), 3);
+    }
+
+    #[test]
+    fn test_cpu_from_time_delta() {
+        let busy = process(1, |p| p.cpu_time_ms = 1_500);
+        // 1 s of CPU time over 2 s of wall clock
+        assert_eq!(busy.clone().with_cpu_delta(500, 2000.0).cpu_usage, 50.0);
+        // Several threads on several cores can go past 100%
+        assert_eq!(busy.clone().with_cpu_delta(0, 1000.0).cpu_usage, 150.0);
+        // A counter that went backwards reads as idle rather than wrapping
+        assert_eq!(busy.clone().with_cpu_delta(9_000, 1000.0).cpu_usage, 0.0);
+        // With no interval the sysinfo figure is kept
+        let unchanged = process(1, |p| p.cpu_usage = 12.0).with_cpu_delta(0, 0.0);
+        assert_eq!(unchanged.cpu_usage, 12.0);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_parse_stat() {
+        let stat = "4242 (tmux: server (1)) S 1 4242 4242 0 -1 4194624 1520 0 3 0 250 75 0 0 20 0 1 0 12000 9334784 1100 18446744073709551615";
+        let details = parse_stat(4242, stat, Some(1_700_000_000)).unwrap();
+        assert_eq!(details.name, "tmux: server (1)");
+        assert_eq!(details.state, "S");
+        assert_eq!(details.parent_pid, Some(1));
+        assert_eq!((details.minor_faults, details.major_faults), (1520, 3));
+        assert_eq!((details.cpu_user_ms, details.cpu_system_ms), (2500, 750));
+        assert_eq!((details.priority, details.nice, details.threads), (20, 0, 1));
+        // 12000 ticks after boot
+        assert_eq!(details.start_time, 1_700_000_120);
+
+        // PID 1's parent is 0, i.e. none; without a boot time the start time is unknown
+        let init = parse_stat(1, "1 (systemd) S 0 1 1 0 -1 4194560 0 0 0 0 0 0 0 0 20 0 1 0 1 0 0", None).unwrap();
+        assert_eq!((init.parent_pid, init.start_time), (None, 0));
+
+        assert!(parse_stat(1, "1 (truncated) S 0 1", None).is_none());
+        assert!(parse_stat(1, "", None).is_none());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_parse_status_rollup_and_io() {
+        let mut details = ProcessDetails::default();
+        parse_status(&mut details, "\
+Name:\tpostgres
+State:\tS (sleeping)
+Uid:\t999\t999\t999\t999
+Gid:\t999\t999\t999\t999
+voluntary_ctxt_switches:\t8812
+nonvoluntary_ctxt_switches:\t17
+");
+        assert_eq!(details.uid, Some(999));
+        assert_eq!((details.voluntary_ctx_switches, details.involuntary_ctx_switches), (8812, 17));
+
+        parse_smaps_rollup(&mut details, "\
+55d0c0a00000-7ffd4d5fe000 ---p 00000000 00:00 0                          [rollup]
+Rss:               25780 kB
+Pss:               10211 kB
+Pss_Anon:           6048 kB
+Swap:                  0 kB
+");
+        assert_eq!(details.rss_bytes, Some(25780 * 1024));
+        // Pss_Anon must not be taken for Pss
+        assert_eq!(details.pss_bytes, Some(10211 * 1024));
+        assert_eq!(details.swap_bytes, Some(0));
+
+        parse_io(&mut details, "\
+rchar: 2012
+wchar: 1400
+syscr: 8
+syscw: 4
+read_bytes: 4096
+write_bytes: 12288
+cancelled_write_bytes: 0
+");
+        assert_eq!((details.io_read_bytes, details.io_write_bytes), (Some(4096), Some(12288)));
+
+        // A kernel without task I/O accounting has no io file fields to find
+        let mut empty = ProcessDetails::default();
+        parse_io(&mut empty, "");
+        assert_eq!(empty.io_read_bytes, None);
+    }
+
+    #[test]
+    fn test_process_row() {
+        let row = ProcessRow::from(&process(7, |p| {
+            p.memory = 4096;
+            p.cpu_time_ms = 250;
+            p.start_time = 1_700_000_000;
+        }));
+        assert_eq!((row.pid, row.name.as_str(), row.memory), (7, "proc7", 4096));
+        assert_eq!((row.cpu_time_ms, row.start_time), (250, 1_700_000_000));
+    }
+}
@@ -0,0 +1,123 @@
+use serde::{Serialize, Deserialize};
+use super::fsroot::FsRoot;
+
+/// Pressure stall information from /proc/pressure (Linux 4.20+).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PressureInfo {
+    pub cpu: Option<ResourcePressure>,
+    pub memory: Option<ResourcePressure>,
+    pub io: Option<ResourcePressure>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResourcePressure {
+    // Share of time at least one task was stalled on the resource
+    pub some: PressureLine,
+    // Share of time all non-idle tasks were stalled at once (absent for cpu on older kernels)
+    pub full: Option<PressureLine>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PressureLine {
+    pub avg10: f64,
+    pub avg60: f64,
+    pub avg300: f64,
+    pub total_us: u64,
+}
+
+#[cfg(target_os = "linux")]
+pub fn collect_pressure(root: &FsRoot) -> anyhow::Result<Option<PressureInfo>> {
+    let read = |resource: &str| -> anyhow::Result<Option<ResourcePressure>> {
+        Ok(root.optional_proc(&format!("pressure/{}", resource))?
+            .and_then(|data| parse_pressure_file(&data)))
+    };
+
+    let info = PressureInfo {
+        cpu: read("cpu")?,
+        memory: read("memory")?,
+        io: read("io")?,
+    };
+
+    // Kernels built without CONFIG_PSI have none of these files, and with psi=0 reading
+    // them fails with EOPNOTSUPP
+    if info.cpu.is_none() && info.memory.is_none() && info.io.is_none() {
+        Ok(None)
+    } else {
+        Ok(Some(info))
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn collect_pressure(_root: &FsRoot) -> anyhow::Result<Option<PressureInfo>> {
+    Ok(None)
+}
+
+#[cfg(target_os = "linux")]
+fn parse_pressure_file(data: &str) -> Option<ResourcePressure> {
+    let mut some = None;
+    let mut full = None;
+
+    // Lines look like: "some avg10=0.13 avg60=0.13 avg300=0.20 total=3406267"
+    for line in data.lines() {
+        let mut fields = line.split_whitespace();
+        let kind = fields.next();
+        let mut parsed = PressureLine::default();
+        for field in fields {
+            match field.split_once('=') {
+                Some(("avg10", v)) => parsed.avg10 = v.parse().unwrap_or(0.0),
+                Some(("avg60", v)) => parsed.avg60 = v.parse().unwrap_or(0.0),
+                Some(("avg300", v)) => parsed.avg300 = v.parse().unwrap_or(0.0),
+                Some(("total", v)) => parsed.total_us = v.parse().unwrap_or(0),
+                _ => {}
+            }
+        }
+        match kind {
+            Some("some") => some = Some(parsed),
+            Some("full") => full = Some(parsed),
+            _ => {}
+        }
+    }
+
+    some.map(|some| ResourcePressure { some, full })
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_some_and_full() {
+        let data = "\
+some avg10=1.53 avg60=0.87 avg300=0.25 total=3406267
+full avg10=0.50 avg60=0.10 avg300=0.02 total=120034
+";
+        let pressure = parse_pressure_file(data).unwrap();
+        assert_eq!(pressure.some.avg10, 1.53);
+        assert_eq!(pressure.some.avg300, 0.25);
+        assert_eq!(pressure.some.total_us, 3406267);
+        let full = pressure.full.unwrap();
+        assert_eq!((full.avg60, full.total_us), (0.10, 120034));
+    }
+
+    #[test]
+    fn test_parse_cpu_without_full() {
+        // cpu has no "full" line before 5.13
+        let pressure = parse_pressure_file("some avg10=0.00 avg60=0.02 avg300=0.00 total=81234\n").unwrap();
+        assert_eq!(pressure.some.avg60, 0.02);
+        assert!(pressure.full.is_none());
+    }
+
+    #[test]
+    fn test_parse_malformed() {
+        assert!(parse_pressure_file("").is_none());
+        // Without a "some" line there's nothing to report
+        assert!(parse_pressure_file("full avg10=1.00 avg60=1.00 avg300=1.00 total=5\n").is_none());
+        assert!(parse_pressure_file("garbage\n").is_none());
+
+        // Unparseable or missing values read as zero instead of dropping the line
+        let pressure = parse_pressure_file("some avg10=x avg60 total=12 extra=7\n").unwrap();
+        assert_eq!(pressure.some.avg10, 0.0);
+        assert_eq!(pressure.some.avg60, 0.0);
+        assert_eq!(pressure.some.total_us, 12);
+    }
+}
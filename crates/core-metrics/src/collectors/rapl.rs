@@ -0,0 +1,195 @@
+use serde::{Serialize, Deserialize};
+use super::fsroot::FsRoot;
+
+/// CPU/DRAM energy use from the RAPL powercap interface (/sys/class/powercap/intel-rapl*).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RaplInfo {
+    pub status: RaplStatus,
+    pub domains: Vec<RaplDomain>,
+    // Sums across sockets; None until two samples are available
+    pub package_watts: Option<f64>,
+    pub core_watts: Option<f64>,
+    pub dram_watts: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RaplStatus {
+    Available,
+    // No powercap RAPL zones on this machine (non-Intel/AMD, VM, non-Linux)
+    #[default]
+    Unsupported,
+    // Zones exist but energy_uj is root-only (Linux 5.10+ default)
+    PermissionDenied,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RaplDomain {
+    pub zone: String,   // e.g. "intel-rapl:0:1"
+    pub name: String,   // e.g. "package-0", "core", "dram"
+    pub energy_uj: u64,
+    pub max_energy_range_uj: u64,
+    // Calculated when comparing with previous snapshots
+    pub watts: Option<f64>,
+}
+
+impl RaplDomain {
+    // Calculate average power since the previous snapshot, allowing for the counter
+    // wrapping at max_energy_range_uj
+    pub fn with_power(mut self, previous: &RaplDomain, time_delta_ms: f64) -> Self {
+        if time_delta_ms <= 0.0 {
+            return self;
+        }
+        let delta_uj = if self.energy_uj >= previous.energy_uj {
+            self.energy_uj - previous.energy_uj
+        } else if self.max_energy_range_uj > 0 {
+            self.max_energy_range_uj.saturating_sub(previous.energy_uj) + self.energy_uj
+        } else {
+            // Wrapped or reset, but with an unknown range there's no telling by how much
+            return self;
+        };
+        self.watts = Some(delta_uj as f64 / 1_000_000.0 / (time_delta_ms / 1000.0));
+        self
+    }
+}
+
+impl RaplInfo {
+    // Calculate per-domain and total power based on previous snapshot
+    pub fn with_power(mut self, previous: &RaplInfo, time_delta_ms: f64) -> Self {
+        self.domains = self
+            .domains
+            .into_iter()
+            .map(|d| match previous.domains.iter().find(|p| p.zone == d.zone) {
+                Some(prev) => d.with_power(prev, time_delta_ms),
+                None => d,
+            })
+            .collect();
+
+        let total = |kind: &str| {
+            let watts: Vec<f64> = self
+                .domains
+                .iter()
+                .filter(|d| d.name == kind || (kind == "package" && d.name.starts_with("package-")))
+                .filter_map(|d| d.watts)
+                .collect();
+            (!watts.is_empty()).then(|| watts.iter().sum())
+        };
+        self.package_watts = total("package");
+        self.core_watts = total("core");
+        self.dram_watts = total("dram");
+        self
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn collect_rapl(root: &FsRoot) -> anyhow::Result<RaplInfo> {
+    collect_rapl_from(&root.sys("class/powercap"))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn collect_rapl(_root: &FsRoot) -> anyhow::Result<RaplInfo> {
+    Ok(RaplInfo::default())
+}
+
+/// Read every intel-rapl zone under a powercap class directory.
+pub fn collect_rapl_from(root: &std::path::Path) -> anyhow::Result<RaplInfo> {
+    use std::fs;
+    use std::io::ErrorKind;
+
+    let mut info = RaplInfo::default();
+    // No powercap driver
+    let Some(entries) = super::fsroot::read_dir_optional(root)? else {
+        return Ok(info);
+    };
+
+    // Zones are flat in the class directory: intel-rapl:0, intel-rapl:0:0, ...
+    let mut zones: Vec<_> = entries
+        .flatten()
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|name| name.starts_with("intel-rapl:"))
+        .collect();
+    zones.sort();
+
+    let mut denied = false;
+    for zone in zones {
+        let dir = root.join(&zone);
+        let read = |file: &str| fs::read_to_string(dir.join(file)).map(|v| v.trim().to_string());
+
+        let energy_uj = match read("energy_uj") {
+            Ok(v) => v.parse().unwrap_or(0),
+            Err(e) => {
+                denied |= e.kind() == ErrorKind::PermissionDenied;
+                continue;
+            }
+        };
+        info.domains.push(RaplDomain {
+            name: read("name").unwrap_or_else(|_| zone.clone()),
+            zone,
+            energy_uj,
+            max_energy_range_uj: read("max_energy_range_uj")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0),
+            watts: None,
+        });
+    }
+
+    info.status = if !info.domains.is_empty() {
+        RaplStatus::Available
+    } else if denied {
+        RaplStatus::PermissionDenied
+    } else {
+        RaplStatus::Unsupported
+    };
+    Ok(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn domain(energy_uj: u64) -> RaplDomain {
+        RaplDomain {
+            zone: "intel-rapl:0".to_string(),
+            name: "package-0".to_string(),
+            energy_uj,
+            max_energy_range_uj: 262_143_328_850,
+            watts: None,
+        }
+    }
+
+    #[test]
+    fn test_power_from_energy_delta() {
+        let watts = domain(30_000_000).with_power(&domain(10_000_000), 1000.0).watts;
+        assert_eq!(watts, Some(20.0));
+    }
+
+    #[test]
+    fn test_power_across_counter_wraparound() {
+        let before = domain(262_143_328_850 - 5_000_000);
+        let watts = domain(5_000_000).with_power(&before, 500.0).watts;
+        assert_eq!(watts, Some(20.0));
+    }
+
+    #[test]
+    fn test_backwards_counter_with_unknown_range() {
+        let mut before = domain(50_000_000);
+        before.max_energy_range_uj = 0;
+        let mut now = domain(1_000_000);
+        now.max_energy_range_uj = 0;
+        assert_eq!(now.with_power(&before, 1000.0).watts, None);
+    }
+
+    #[test]
+    fn test_totals_sum_packages() {
+        let mut second = domain(0);
+        second.zone = "intel-rapl:1".to_string();
+        second.name = "package-1".to_string();
+        let previous = RaplInfo { domains: vec![domain(0), second.clone()], ..Default::default() };
+        second.energy_uj = 15_000_000;
+        let current = RaplInfo { domains: vec![domain(25_000_000), second], ..Default::default() };
+
+        let current = current.with_power(&previous, 1000.0);
+        assert_eq!(current.package_watts, Some(40.0));
+        assert_eq!(current.dram_watts, None);
+    }
+}
@@ -0,0 +1,367 @@
+use serde::{Serialize, Deserialize};
+use std::time::{Duration, Instant};
+use sysinfo::{CpuRefreshKind, MemoryRefreshKind, ProcessRefreshKind, RefreshKind};
+use super::snapshot::Snapshot;
+use super::fsroot::FsRoot;
+use crate::cfg::Collectors;
+
+/// What a collector gets to see while filling in its part of a snapshot.
+pub struct CollectContext<'a> {
+    pub now: chrono::DateTime<chrono::Utc>,
+    pub sys: &'a sysinfo::System,
+    pub previous: Option<&'a Snapshot>,
+    pub root: &'a FsRoot,   // Where /proc and /sys are read from
+    // Milliseconds since this collector last ran, for turning counters into rates.
+    // Collectors on a slower interval than the tick must use this rather than `previous.ts`
+    pub elapsed_ms: Option<f64>,
+}
+
+impl CollectContext<'_> {
+    // The previous value of a field together with the time since it was collected
+    pub fn rate_base<T>(&self, field: impl Fn(&Snapshot) -> Option<&T>) -> Option<(&T, f64)> {
+        Some((field(self.previous?)?, self.elapsed_ms?))
+    }
+}
+
+/// A source of metrics that fills in part of a `Snapshot`.
+pub trait Collector: Send {
+    fn name(&self) -> &'static str;
+
+    // Whether the `[collectors]` toggles allow this collector to run
+    fn enabled(&self, _toggles: &Collectors) -> bool {
+        true
+    }
+
+    // How often to run; None means every tick. Can be overridden per collector in the config
+    fn interval(&self) -> Option<Duration> {
+        None
+    }
+
+    // The parts of `sysinfo::System` this collector reads; only those get refreshed
+    fn refresh_kind(&self) -> RefreshKind {
+        RefreshKind::new()
+    }
+
+    fn collect(&mut self, ctx: &CollectContext, snapshot: &mut Snapshot) -> anyhow::Result<()>;
+}
+
+/// Run statistics for one collector, reported with every snapshot.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CollectorStatus {
+    pub name: String,
+    pub enabled: bool,
+    pub interval_ms: Option<u64>,
+    pub last_run: Option<i64>,   // Unix timestamp, milliseconds
+    pub last_duration_ms: f64,
+    pub runs: u64,
+    pub failures: u64,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<i64>,
+    // When the collector last succeeded. Its fields keep their values from then (or
+    // whatever it filled in before failing), so they are stale until it succeeds again
+    #[serde(default)]
+    pub last_success: Option<i64>,
+    #[serde(default)]
+    pub stale: bool,
+}
+
+struct Entry {
+    collector: Box<dyn Collector>,
+    interval: Option<Duration>,
+    status: CollectorStatus,
+}
+
+impl Entry {
+    fn is_due(&self, now_ms: i64) -> bool {
+        self.status.enabled
+            && match (self.interval, self.status.last_run) {
+                (Some(interval), Some(last)) => now_ms - last >= interval.as_millis() as i64,
+                _ => true,
+            }
+    }
+}
+
+// Union of what two collectors need refreshed. Only the process fields the built-in
+// collectors use are merged; user, cmd, exe and friends are never requested
+fn merge_refresh_kinds(a: RefreshKind, b: RefreshKind) -> RefreshKind {
+    let mut kind = RefreshKind::new();
+    match (a.cpu(), b.cpu()) {
+        (None, None) => {}
+        (x, y) => {
+            let (x, y) = (x.unwrap_or_else(CpuRefreshKind::new), y.unwrap_or_else(CpuRefreshKind::new));
+            let mut cpu = CpuRefreshKind::new();
+            if x.cpu_usage() || y.cpu_usage() {
+                cpu = cpu.with_cpu_usage();
+            }
+            if x.frequency() || y.frequency() {
+                cpu = cpu.with_frequency();
+            }
+            kind = kind.with_cpu(cpu);
+        }
+    }
+    match (a.memory(), b.memory()) {
+        (None, None) => {}
+        (x, y) => {
+            let (x, y) = (x.unwrap_or_else(MemoryRefreshKind::new), y.unwrap_or_else(MemoryRefreshKind::new));
+            let mut memory = MemoryRefreshKind::new();
+            if x.ram() || y.ram() {
+                memory = memory.with_ram();
+            }
+            if x.swap() || y.swap() {
+                memory = memory.with_swap();
+            }
+            kind = kind.with_memory(memory);
+        }
+    }
+    match (a.processes(), b.processes()) {
+        (None, None) => {}
+        (x, y) => {
+            let (x, y) = (x.unwrap_or_else(ProcessRefreshKind::new), y.unwrap_or_else(ProcessRefreshKind::new));
+            let mut processes = ProcessRefreshKind::new();
+            if x.cpu() || y.cpu() {
+                processes = processes.with_cpu();
+            }
+            if x.memory() || y.memory() {
+                processes = processes.with_memory();
+            }
+            if x.disk_usage() || y.disk_usage() {
+                processes = processes.with_disk_usage();
+            }
+            kind = kind.with_processes(processes);
+        }
+    }
+    kind
+}
+
+/// Ordered set of collectors that together build each snapshot.
+pub struct CollectorRegistry {
+    toggles: Collectors,
+    root: FsRoot,
+    entries: Vec<Entry>,
+}
+
+impl CollectorRegistry {
+    pub fn new(toggles: Collectors) -> Self {
+        Self { toggles, root: FsRoot::default(), entries: Vec::new() }
+    }
+
+    // Read /proc and /sys somewhere other than the host's own, e.g. from a capture
+    pub fn with_root(mut self, root: FsRoot) -> Self {
+        self.root = root;
+        self
+    }
+
+    /// A registry holding every built-in collector. SMART health is only attached to
+    /// filesystems when given a monitor.
+    pub fn with_builtin(
+        toggles: Collectors,
+        process_options: super::process::ProcessOptions,
+        filesystem_options: super::disk::FilesystemOptions,
+        smart: Option<super::storage_health::SmartMonitor>,
+    ) -> Self {
+        let mut registry = Self::new(toggles);
+        for collector in super::builtin::builtin_collectors(process_options, filesystem_options, smart) {
+            registry.register(collector);
+        }
+        registry
+    }
+
+    // Collectors run in registration order, so one may read what an earlier one filled in
+    pub fn register(&mut self, collector: Box<dyn Collector>) {
+        let name = collector.name();
+        let enabled = !self.toggles.disabled.iter().any(|d| d == name) && collector.enabled(&self.toggles);
+        let interval = match self.toggles.intervals_ms.get(name) {
+            Some(ms) => Some(Duration::from_millis(*ms)),
+            None => collector.interval(),
+        };
+        let status = CollectorStatus {
+            name: name.to_string(),
+            enabled,
+            interval_ms: interval.map(|i| i.as_millis() as u64),
+            ..Default::default()
+        };
+        self.entries.push(Entry { collector, interval, status });
+    }
+
+    /// Refresh just the parts of `sys` that the collectors due at `now` will read.
+    pub fn refresh(&self, sys: &mut sysinfo::System, now: chrono::DateTime<chrono::Utc>) {
+        let now_ms = now.timestamp_millis();
+        let kind = self
+            .entries
+            .iter()
+            .filter(|e| e.is_due(now_ms))
+            .fold(RefreshKind::new(), |kind, e| merge_refresh_kinds(kind, e.collector.refresh_kind()));
+        sys.refresh_specifics(kind);
+    }
+
+    /// Build a snapshot from every enabled collector that is due.
+    pub fn collect(
+        &mut self,
+        now: chrono::DateTime<chrono::Utc>,
+        sys: &sysinfo::System,
+        previous: Option<&Snapshot>,
+    ) -> Snapshot {
+        let now_ms = now.timestamp_millis();
+        // Collectors that aren't due this tick keep their previous values
+        let mut snapshot = previous.cloned().unwrap_or_default();
+        snapshot.ts = now_ms;
+
+        for entry in self.entries.iter_mut().filter(|e| e.is_due(now_ms)) {
+            let last_run = entry.status.last_run.or(previous.map(|p| p.ts));
+
+            let ctx = CollectContext {
+                now,
+                sys,
+                previous,
+                root: &self.root,
+                elapsed_ms: last_run.map(|last| (now_ms - last) as f64),
+            };
+            let started = Instant::now();
+            let result = entry.collector.collect(&ctx, &mut snapshot);
+
+            let status = &mut entry.status;
+            status.last_duration_ms = started.elapsed().as_secs_f64() * 1000.0;
+            status.last_run = Some(now_ms);
+            status.runs += 1;
+            match result {
+                Ok(()) => {
+                    status.last_success = Some(now_ms);
+                    status.stale = false;
+                }
+                Err(e) => {
+                    tracing::warn!("collector {} failed: {:#}", status.name, e);
+                    status.failures += 1;
+                    status.last_error = Some(format!("{:#}", e));
+                    status.last_error_at = Some(now_ms);
+                    status.stale = true;
+                }
+            }
+        }
+
+        snapshot.collectors = self.status();
+        snapshot
+    }
+
+    pub fn status(&self) -> Vec<CollectorStatus> {
+        self.entries.iter().map(|e| e.status.clone()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    struct LoadCounter {
+        interval: Option<Duration>,
+        fail: bool,
+    }
+
+    impl LoadCounter {
+        fn every_tick(fail: bool) -> Box<Self> {
+            Box::new(Self { interval: None, fail })
+        }
+    }
+
+    impl Collector for LoadCounter {
+        fn name(&self) -> &'static str {
+            "counter"
+        }
+
+        fn enabled(&self, toggles: &Collectors) -> bool {
+            toggles.load
+        }
+
+        fn interval(&self) -> Option<Duration> {
+            self.interval
+        }
+
+        fn collect(&mut self, ctx: &CollectContext, snapshot: &mut Snapshot) -> anyhow::Result<()> {
+            if self.fail {
+                anyhow::bail!("no data");
+            }
+            snapshot.load1 = ctx.previous.map_or(1.0, |p| p.load1 + 1.0);
+            Ok(())
+        }
+    }
+
+    fn at(ms: i64) -> chrono::DateTime<chrono::Utc> {
+        chrono::Utc.timestamp_millis_opt(ms).unwrap()
+    }
+
+    #[test]
+    fn test_interval_keeps_previous_value() {
+        let sys = sysinfo::System::new();
+        let mut registry = CollectorRegistry::new(Collectors::default());
+        registry.register(Box::new(LoadCounter { interval: Some(Duration::from_secs(2)), fail: false }));
+
+        let first = registry.collect(at(0), &sys, None);
+        let second = registry.collect(at(1000), &sys, Some(&first));
+        let third = registry.collect(at(2000), &sys, Some(&second));
+
+        assert_eq!(first.load1, 1.0);
+        // Not due yet, so the value is carried over
+        assert_eq!(second.load1, 1.0);
+        assert_eq!(third.load1, 2.0);
+        assert_eq!(third.collectors[0].runs, 2);
+        assert_eq!(third.collectors[0].last_run, Some(2000));
+    }
+
+    #[test]
+    fn test_merge_refresh_kinds() {
+        let cpu = RefreshKind::new().with_cpu(CpuRefreshKind::new().with_cpu_usage());
+        let processes = RefreshKind::new().with_processes(ProcessRefreshKind::new().with_memory());
+        let tree = RefreshKind::new().with_processes(ProcessRefreshKind::new().with_cpu());
+
+        let kind = [cpu, processes, tree].into_iter().fold(RefreshKind::new(), merge_refresh_kinds);
+        assert!(kind.cpu().unwrap().cpu_usage());
+        assert!(kind.memory().is_none());
+        let processes = kind.processes().unwrap();
+        assert!(processes.cpu() && processes.memory() && !processes.disk_usage());
+    }
+
+    #[test]
+    fn test_toggles_and_errors() {
+        let sys = sysinfo::System::new();
+        let toggles = Collectors { load: false, ..Default::default() };
+        let mut registry = CollectorRegistry::new(toggles);
+        registry.register(LoadCounter::every_tick(false));
+        let snapshot = registry.collect(at(0), &sys, None);
+        assert_eq!(snapshot.load1, 0.0);
+        assert!(!snapshot.collectors[0].enabled);
+        assert_eq!(snapshot.collectors[0].runs, 0);
+
+        let mut registry = CollectorRegistry::new(Collectors::default());
+        registry.register(LoadCounter::every_tick(true));
+        let snapshot = registry.collect(at(0), &sys, None);
+        assert_eq!(snapshot.collectors[0].failures, 1);
+        assert_eq!(snapshot.collectors[0].last_error.as_deref(), Some("no data"));
+        assert_eq!(snapshot.collectors[0].last_error_at, Some(0));
+    }
+
+    #[test]
+    fn test_failed_collector_marks_values_stale() {
+        let sys = sysinfo::System::new();
+        let mut registry = CollectorRegistry::new(Collectors::default());
+        registry.register(LoadCounter::every_tick(false));
+        let first = registry.collect(at(0), &sys, None);
+        assert!(!first.collectors[0].stale);
+
+        // Same registry, but the collector starts failing
+        registry.entries[0].collector = LoadCounter::every_tick(true);
+        let second = registry.collect(at(1000), &sys, Some(&first));
+        // The old value is carried over under the new timestamp, flagged as stale
+        assert_eq!(second.ts, 1000);
+        assert_eq!(second.load1, 1.0);
+        let status = &second.collectors[0];
+        assert!(status.stale);
+        assert_eq!(status.last_success, Some(0));
+        assert_eq!(status.last_error_at, Some(1000));
+
+        registry.entries[0].collector = LoadCounter::every_tick(false);
+        let third = registry.collect(at(2000), &sys, Some(&second));
+        assert_eq!(third.load1, 2.0);
+        assert!(!third.collectors[0].stale);
+        assert_eq!(third.collectors[0].last_success, Some(2000));
+    }
+}
@@ -1,11 +1,142 @@
 use serde::{Serialize, Deserialize};
+use super::fsroot::{read_dir_optional, FsRoot};
+use sysinfo::Component;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SensorInfo {
-    pub component: String,
-    pub temperature: f32,
-    pub unit: String,
-    pub label: String,
+    pub component: String,   // Chip name, e.g. "coretemp" or "nct6775"
+    pub label: String,       // Reading name on that chip, e.g. "Package id 0" or "fan2"
+    pub value: f32,
+    pub unit: SensorUnit,
+    // Alarm floor for fans and voltages (fanN_min, inN_min); a fan below it is stalling
+    #[serde(default)]
+    pub min: Option<f32>,
+    pub max: Option<f32>,
+    pub critical: Option<f32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SensorUnit {
+    Celsius,
+    Rpm,
+    Volts,
+    Amps,
+    Watts,
+}
+
+impl SensorUnit {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            SensorUnit::Celsius => "°C",
+            SensorUnit::Rpm => "RPM",
+            SensorUnit::Volts => "V",
+            SensorUnit::Amps => "A",
+            SensorUnit::Watts => "W",
+        }
+    }
+}
+
+impl SensorInfo {
+    // Temperature-only fallback for platforms without hwmon
+    pub fn from_component(component: &Component) -> Self {
+        Self {
+            component: "sysinfo".to_string(),
+            label: component.label().to_string(),
+            value: component.temperature(),
+            unit: SensorUnit::Celsius,
+            min: None,
+            max: Some(component.max()).filter(|m| m.is_finite() && *m > 0.0),
+            critical: component.critical(),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn collect_hwmon_sensors(root: &FsRoot) -> anyhow::Result<Vec<SensorInfo>> {
+    collect_hwmon_sensors_from(&root.sys("class/hwmon"))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn collect_hwmon_sensors(_root: &FsRoot) -> anyhow::Result<Vec<SensorInfo>> {
+    Ok(Vec::new())
+}
+
+/// Walk a hwmon class directory (normally /sys/class/hwmon) and read every
+/// temperature, fan, voltage, current and power channel of every chip.
+pub fn collect_hwmon_sensors_from(root: &std::path::Path) -> anyhow::Result<Vec<SensorInfo>> {
+    let mut sensors = Vec::new();
+    // No hwmon drivers loaded
+    let Some(entries) = read_dir_optional(root)? else {
+        return Ok(sensors);
+    };
+    
+    let mut chips: Vec<_> = entries.flatten().map(|e| e.path()).collect();
+    chips.sort();
+    
+    for chip in chips {
+        // Older drivers keep their attributes in the device/ subdirectory
+        let dir = if chip.join("name").exists() { chip.clone() } else { chip.join("device") };
+        let read = |file: &str| {
+            std::fs::read_to_string(dir.join(file)).ok().map(|v| v.trim().to_string())
+        };
+        let chip_name = read("name").unwrap_or_else(|| {
+            chip.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
+        });
+        
+        let Ok(files) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut inputs: Vec<String> = files
+            .flatten()
+            .map(|f| f.file_name().to_string_lossy().to_string())
+            .filter(|f| f.ends_with("_input") || (f.starts_with("power") && f.ends_with("_average")))
+            .collect();
+        inputs.sort_by_key(|f| channel_sort_key(f));
+        
+        for input in inputs {
+            let (channel, _) = input.rsplit_once('_').unwrap_or((&input, ""));
+            // power channels may expose both _input and _average; keep one
+            if input.ends_with("_average") && dir.join(format!("{}_input", channel)).exists() {
+                continue;
+            }
+            
+            // sysfs units: millidegrees, RPM, millivolts, milliamps, microwatts
+            let (unit, scale) = match channel.trim_end_matches(|c: char| c.is_ascii_digit()) {
+                "temp" => (SensorUnit::Celsius, 1_000.0),
+                "fan" => (SensorUnit::Rpm, 1.0),
+                "in" => (SensorUnit::Volts, 1_000.0),
+                "curr" => (SensorUnit::Amps, 1_000.0),
+                "power" => (SensorUnit::Watts, 1_000_000.0),
+                _ => continue,
+            };
+            let value_of = |file: &str| {
+                read(file).and_then(|v| v.parse::<f64>().ok()).map(|v| (v / scale) as f32)
+            };
+            let Some(value) = value_of(&input) else {
+                continue;
+            };
+            
+            sensors.push(SensorInfo {
+                component: chip_name.clone(),
+                label: read(&format!("{}_label", channel)).unwrap_or_else(|| channel.to_string()),
+                value,
+                unit,
+                min: value_of(&format!("{}_min", channel)),
+                max: value_of(&format!("{}_max", channel)),
+                critical: value_of(&format!("{}_crit", channel)),
+            });
+        }
+    }
+    
+    Ok(sensors)
+}
+
+// Group channels by type and order them numerically (temp2 before temp10)
+fn channel_sort_key(file: &str) -> (String, u32) {
+    let channel = file.split('_').next().unwrap_or(file);
+    let kind = channel.trim_end_matches(|c: char| c.is_ascii_digit());
+    let index = channel[kind.len()..].parse().unwrap_or(0);
+    (kind.to_string(), index)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,13 +145,22 @@ pub struct BatteryInfo {
     pub charge_percent: f32,
     pub health_percent: f32,
     pub state: BatteryState,
+    #[serde(default)]
+    pub power_draw_watts: f32,
+    #[serde(default)]
+    pub time_to_empty_secs: Option<u64>,
+    #[serde(default)]
+    pub time_to_full_secs: Option<u64>,
+    #[serde(default)]
+    pub cycle_count: Option<u64>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum BatteryState {
     Charging,
     Discharging,
     Full,
+    NotCharging,   // On AC but held below full, e.g. by a charge threshold
     Unknown,
 }
 
@@ -31,6 +171,263 @@ impl BatteryInfo {
             charge_percent,
             health_percent,
             state,
+            power_draw_watts: 0.0,
+            time_to_empty_secs: None,
+            time_to_full_secs: None,
+            cycle_count: None,
+        }
+    }
+}
+
+/// Batteries and AC adapter state read from a power_supply class directory
+/// (normally /sys/class/power_supply).
+#[derive(Debug, Clone, Default)]
+pub struct PowerSupplies {
+    pub batteries: Vec<BatteryInfo>,
+    // None when the machine reports no mains adapter at all
+    pub ac_online: Option<bool>,
+}
+
+#[cfg(target_os = "linux")]
+pub fn collect_power_supplies(root: &FsRoot) -> anyhow::Result<PowerSupplies> {
+    collect_power_supplies_from(&root.sys("class/power_supply"))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn collect_power_supplies(_root: &FsRoot) -> anyhow::Result<PowerSupplies> {
+    Ok(PowerSupplies::default())
+}
+
+pub fn collect_power_supplies_from(root: &std::path::Path) -> anyhow::Result<PowerSupplies> {
+    let mut supplies = PowerSupplies::default();
+    let Some(entries) = read_dir_optional(root)? else {
+        return Ok(supplies);
+    };
+    
+    let mut entries: Vec<_> = entries.flatten().collect();
+    entries.sort_by_key(|e| e.file_name());
+    
+    for entry in entries {
+        let dir = entry.path();
+        let read = |file: &str| {
+            std::fs::read_to_string(dir.join(file))
+                .ok()
+                .map(|v| v.trim().to_string())
+        };
+        
+        match read("type").as_deref() {
+            Some("Mains") | Some("USB") => {
+                if read("online").as_deref() == Some("1") {
+                    supplies.ac_online = Some(true);
+                } else if supplies.ac_online.is_none() {
+                    supplies.ac_online = Some(false);
+                }
+            }
+            // Peripheral batteries (mice, keyboards) report scope "Device"
+            Some("Battery") if read("scope").as_deref() != Some("Device") => {
+                let name = entry.file_name().to_string_lossy().to_string();
+                supplies.batteries.push(read_battery(name, &read));
+            }
+            _ => {}
         }
     }
+    
+    Ok(supplies)
+}
+
+fn read_battery(name: String, read: &dyn Fn(&str) -> Option<String>) -> BatteryInfo {
+    let number = |file: &str| read(file).and_then(|v| v.parse::<f64>().ok());
+    
+    // Drivers report either energy (µWh) or charge (µAh); convert charge to energy
+    // with the design voltage (µV) so everything below works in µWh and µW
+    let voltage = number("voltage_min_design").or_else(|| number("voltage_now"));
+    let energy = |energy_file: &str, charge_file: &str| {
+        number(energy_file).or_else(|| Some(number(charge_file)? * voltage? / 1_000_000.0))
+    };
+    let energy_now = energy("energy_now", "charge_now");
+    let energy_full = energy("energy_full", "charge_full");
+    let energy_full_design = energy("energy_full_design", "charge_full_design");
+    let power_now = number("power_now")
+        .or_else(|| Some(number("current_now")? * number("voltage_now")? / 1_000_000.0))
+        .map(f64::abs);
+    
+    let charge_percent = number("capacity").or_else(|| match (energy_now, energy_full) {
+        (Some(now), Some(full)) if full > 0.0 => Some(now / full * 100.0),
+        _ => None,
+    });
+    let health_percent = match (energy_full, energy_full_design) {
+        (Some(full), Some(design)) if design > 0.0 => full / design * 100.0,
+        _ => 100.0,
+    };
+    
+    let state = match read("status").as_deref() {
+        Some("Charging") => BatteryState::Charging,
+        Some("Discharging") => BatteryState::Discharging,
+        Some("Full") => BatteryState::Full,
+        Some("Not charging") => BatteryState::NotCharging,
+        _ => BatteryState::Unknown,
+    };
+    
+    let mut battery = BatteryInfo::new(
+        name,
+        charge_percent.unwrap_or(0.0) as f32,
+        health_percent as f32,
+        state,
+    );
+    battery.power_draw_watts = power_now.map_or(0.0, |p| (p / 1_000_000.0) as f32);
+    battery.cycle_count = read("cycle_count")
+        .and_then(|v| v.parse::<u64>().ok())
+        // Many drivers report 0 when they don't track cycles
+        .filter(|c| *c > 0);
+    
+    // Prefer the driver's own estimate, otherwise derive it from the current draw
+    let hours_to_secs = |hours: f64| (hours * 3600.0) as u64;
+    match (&battery.state, power_now.filter(|p| *p > 0.0)) {
+        (BatteryState::Discharging, power) => {
+            battery.time_to_empty_secs = read("time_to_empty_now")
+                .and_then(|v| v.parse().ok())
+                .or_else(|| Some(hours_to_secs(energy_now? / power?)));
+        }
+        (BatteryState::Charging, power) => {
+            battery.time_to_full_secs = read("time_to_full_now")
+                .and_then(|v| v.parse().ok())
+                .or_else(|| Some(hours_to_secs((energy_full? - energy_now?).max(0.0) / power?)));
+        }
+        _ => {}
+    }
+    
+    battery
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    
+    fn fixture_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("zek-power-supply-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+    
+    // One sysfs device directory (a power supply, an hwmon chip) with an attribute per file
+    fn write_attrs(root: &Path, name: &str, files: &[(&str, &str)]) {
+        let dir = root.join(name);
+        fs::create_dir_all(&dir).unwrap();
+        for (file, value) in files {
+            fs::write(dir.join(file), format!("{}\n", value)).unwrap();
+        }
+    }
+    
+    #[test]
+    fn test_energy_based_battery_discharging() {
+        let root = fixture_dir("energy");
+        write_attrs(&root, "AC", &[("type", "Mains"), ("online", "0")]);
+        write_attrs(&root, "BAT0", &[
+            ("type", "Battery"),
+            ("status", "Discharging"),
+            ("capacity", "50"),
+            ("energy_now", "25000000"),
+            ("energy_full", "50000000"),
+            ("energy_full_design", "62500000"),
+            ("power_now", "12500000"),
+            ("cycle_count", "321"),
+        ]);
+        
+        let supplies = collect_power_supplies_from(&root).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+        
+        assert_eq!(supplies.ac_online, Some(false));
+        assert_eq!(supplies.batteries.len(), 1);
+        let bat = &supplies.batteries[0];
+        assert_eq!(bat.state, BatteryState::Discharging);
+        assert_eq!(bat.charge_percent, 50.0);
+        assert_eq!(bat.health_percent, 80.0);
+        assert_eq!(bat.power_draw_watts, 12.5);
+        assert_eq!(bat.time_to_empty_secs, Some(7200));
+        assert_eq!(bat.cycle_count, Some(321));
+    }
+    
+    #[test]
+    fn test_charge_based_battery_charging() {
+        let root = fixture_dir("charge");
+        write_attrs(&root, "ADP1", &[("type", "Mains"), ("online", "1")]);
+        write_attrs(&root, "BAT1", &[
+            ("type", "Battery"),
+            ("status", "Charging"),
+            ("charge_now", "2000000"),
+            ("charge_full", "4000000"),
+            ("charge_full_design", "4000000"),
+            ("voltage_min_design", "10000000"),
+            ("voltage_now", "10000000"),
+            ("current_now", "1000000"),
+            ("cycle_count", "0"),
+        ]);
+        write_attrs(&root, "hidpp_battery_0", &[("type", "Battery"), ("scope", "Device"), ("capacity", "90")]);
+        
+        let supplies = collect_power_supplies_from(&root).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+        
+        assert_eq!(supplies.ac_online, Some(true));
+        assert_eq!(supplies.batteries.len(), 1);
+        let bat = &supplies.batteries[0];
+        assert_eq!(bat.state, BatteryState::Charging);
+        assert_eq!(bat.charge_percent, 50.0);
+        assert_eq!(bat.health_percent, 100.0);
+        assert_eq!(bat.power_draw_watts, 10.0);
+        assert_eq!(bat.time_to_full_secs, Some(7200));
+        assert_eq!(bat.cycle_count, None);
+    }
+    
+    #[test]
+    fn test_missing_power_supply_dir() {
+        let supplies = collect_power_supplies_from(Path::new("/nonexistent/power_supply")).unwrap();
+        assert!(supplies.batteries.is_empty());
+        assert_eq!(supplies.ac_online, None);
+    }
+    
+    #[test]
+    fn test_hwmon_readings_and_thresholds() {
+        let root = fixture_dir("hwmon");
+        write_attrs(&root, "hwmon0", &[
+            ("name", "coretemp"),
+            ("temp1_input", "54000"),
+            ("temp1_label", "Package id 0"),
+            ("temp1_max", "80000"),
+            ("temp1_crit", "100000"),
+            ("temp10_input", "48500"),
+        ]);
+        write_attrs(&root, "hwmon1", &[
+            ("name", "nct6775"),
+            ("fan2_input", "1200"),
+            ("fan2_min", "600"),
+            ("in0_input", "1032"),
+            ("curr1_input", "2500"),
+            ("power1_average", "65000000"),
+        ]);
+        
+        let sensors = collect_hwmon_sensors_from(&root).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+        
+        assert_eq!(sensors.len(), 6);
+        let package = &sensors[0];
+        assert_eq!(package.component, "coretemp");
+        assert_eq!(package.label, "Package id 0");
+        assert_eq!(package.value, 54.0);
+        assert_eq!(package.unit, SensorUnit::Celsius);
+        assert_eq!(package.max, Some(80.0));
+        assert_eq!(package.critical, Some(100.0));
+        assert_eq!(sensors[1].label, "temp10");
+        
+        let find = |unit: SensorUnit| sensors.iter().find(|s| s.unit == unit).unwrap();
+        assert_eq!(find(SensorUnit::Rpm).value, 1200.0);
+        assert_eq!(find(SensorUnit::Rpm).component, "nct6775");
+        assert_eq!(find(SensorUnit::Rpm).min, Some(600.0));
+        assert_eq!(find(SensorUnit::Rpm).max, None);
+        assert_eq!(find(SensorUnit::Volts).value, 1.032);
+        assert_eq!(find(SensorUnit::Amps).value, 2.5);
+        assert_eq!(find(SensorUnit::Watts).value, 65.0);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,763 @@
+use serde::{Serialize, Deserialize};
+use super::fsroot::{read_optional, FsRoot};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use super::disk::DiskInfo;
+
+/// Software RAID, ZFS and device-mapper state.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StorageHealth {
+    pub md_arrays: Vec<MdArray>,
+    pub zfs_arc: Option<ZfsArcStats>,
+    pub zfs_pools: Vec<ZfsPool>,
+    pub dm_devices: Vec<DmDevice>,
+    // md arrays running degraded plus ZFS pools not ONLINE; zero on a healthy host
+    pub degraded_arrays: u32,
+}
+
+/// One array from /proc/mdstat.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MdArray {
+    pub name: String,          // "md0"
+    pub state: String,         // "active" or "inactive"
+    pub read_only: bool,
+    pub level: Option<String>, // "raid1", "raid5", ...; None while inactive
+    pub members: Vec<MdMember>,
+    pub total_devices: Option<u32>,
+    pub active_devices: Option<u32>,
+    pub member_status: Option<String>,   // "[U_U]": one character per slot, '_' is missing
+    pub degraded: bool,
+    pub sync: Option<MdSync>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MdMember {
+    pub device: String,
+    pub role: u32,
+    pub failed: bool,
+    pub spare: bool,
+}
+
+/// A resync, recovery, check or reshape in progress (or pending).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MdSync {
+    pub action: String,   // "resync", "recovery", "check", "reshape"
+    pub progress_percent: Option<f64>,   // None while DELAYED or PENDING
+    pub finish_minutes: Option<f64>,
+    pub speed_kbps: Option<u64>,
+}
+
+/// ZFS ARC counters from /proc/spl/kstat/zfs/arcstats.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ZfsArcStats {
+    pub size: u64,
+    pub target_size: u64,   // "c"
+    pub min_size: u64,
+    pub max_size: u64,
+    pub hits: u64,
+    pub misses: u64,
+    pub l2_hits: u64,
+    pub l2_misses: u64,
+    pub l2_size: u64,
+    pub memory_throttle_count: u64,
+    // Hit ratio over the interval, calculated when comparing with previous snapshots
+    pub hit_percent: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ZfsPool {
+    pub name: String,
+    pub state: String,   // "ONLINE", "DEGRADED", "FAULTED", "SUSPENDED", ...
+}
+
+impl ZfsPool {
+    pub fn healthy(&self) -> bool {
+        self.state == "ONLINE"
+    }
+}
+
+/// A device-mapper target from /sys/block/dm-*.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DmDevice {
+    pub device: String,   // "dm-0"
+    pub name: String,     // "vg0-root", as under /dev/mapper
+    pub uuid: String,
+    pub kind: DmKind,
+    pub lvm: Option<LvmVolume>,
+    pub slaves: Vec<String>,   // Underlying block devices
+    pub suspended: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DmKind {
+    Lvm,
+    Crypt,
+    Multipath,
+    #[default]
+    Other,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LvmVolume {
+    pub volume_group: String,
+    pub logical_volume: String,
+}
+
+impl ZfsArcStats {
+    // Calculate the interval hit ratio based on previous snapshot
+    pub fn with_rates(mut self, previous: &ZfsArcStats) -> Self {
+        let hits = self.hits.saturating_sub(previous.hits);
+        let misses = self.misses.saturating_sub(previous.misses);
+        if hits + misses > 0 {
+            self.hit_percent = Some(hits as f64 / (hits + misses) as f64 * 100.0);
+        }
+        self
+    }
+}
+
+impl StorageHealth {
+    pub fn with_rates(mut self, previous: &StorageHealth) -> Self {
+        if let (Some(arc), Some(prev_arc)) = (self.zfs_arc.take(), previous.zfs_arc.as_ref()) {
+            self.zfs_arc = Some(arc.with_rates(prev_arc));
+        }
+        self
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn collect_storage_health(root: &FsRoot) -> anyhow::Result<StorageHealth> {
+    collect_storage_health_from(&root.proc, &root.sys)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn collect_storage_health(_root: &FsRoot) -> anyhow::Result<StorageHealth> {
+    Ok(StorageHealth::default())
+}
+
+/// Read mdstat and ZFS kstats under a /proc style directory and dm devices under a /sys one.
+pub fn collect_storage_health_from(proc_root: &Path, sys_root: &Path) -> anyhow::Result<StorageHealth> {
+    // mdstat only exists with the md driver loaded, arcstats with the ZFS module
+    let md_arrays = read_optional(&proc_root.join("mdstat"))?
+        .map(|data| parse_mdstat(&data))
+        .unwrap_or_default();
+    let kstat_zfs = proc_root.join("spl/kstat/zfs");
+    let zfs_arc = read_optional(&kstat_zfs.join("arcstats"))?
+        .map(|data| parse_arcstats(&data));
+    let zfs_pools = collect_zfs_pools_from(&kstat_zfs);
+    let dm_devices = collect_dm_devices_from(&sys_root.join("block"));
+
+    let degraded_arrays = md_arrays.iter().filter(|a| a.degraded).count()
+        + zfs_pools.iter().filter(|p| !p.healthy()).count();
+
+    Ok(StorageHealth {
+        md_arrays,
+        zfs_arc,
+        zfs_pools,
+        dm_devices,
+        degraded_arrays: degraded_arrays as u32,
+    })
+}
+
+/// Parse the contents of /proc/mdstat.
+pub fn parse_mdstat(data: &str) -> Vec<MdArray> {
+    let mut arrays: Vec<MdArray> = Vec::new();
+
+    for line in data.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        // "md1 : active raid5 sdd1[3] sdc1[1] sdb2[0](F)"
+        if let Some((name, rest)) = line.split_once(" : ").filter(|(name, _)| name.starts_with("md")) {
+            let mut array = MdArray { name: name.trim().to_string(), ..Default::default() };
+            let mut tokens = rest.split_whitespace();
+            array.state = tokens.next().unwrap_or_default().to_string();
+            for token in tokens {
+                if token.starts_with('(') {
+                    array.read_only |= token.contains("read-only");
+                } else if let Some(member) = parse_md_member(token) {
+                    array.members.push(member);
+                } else if array.level.is_none() {
+                    array.level = Some(token.to_string());
+                }
+            }
+            arrays.push(array);
+            continue;
+        }
+
+        // Status lines belong to the last array header
+        let Some(array) = arrays.last_mut() else {
+            continue;
+        };
+        for token in trimmed.split_whitespace() {
+            let Some(inner) = token.strip_prefix('[').and_then(|t| t.strip_suffix(']')) else {
+                continue;
+            };
+            if let Some((total, active)) = inner.split_once('/') {
+                array.total_devices = total.parse().ok();
+                array.active_devices = active.parse().ok();
+            } else if !inner.is_empty() && inner.chars().all(|c| c == 'U' || c == '_') {
+                array.member_status = Some(token.to_string());
+            }
+        }
+        if let Some(sync) = parse_md_sync(trimmed) {
+            array.sync = Some(sync);
+        }
+    }
+
+    for array in arrays.iter_mut() {
+        let missing_slots = matches!((array.total_devices, array.active_devices), (Some(t), Some(a)) if a < t);
+        array.degraded = array.state == "active"
+            && (missing_slots || array.members.iter().any(|m| m.failed));
+    }
+    arrays
+}
+
+// "sdb2[0](F)" -> failed member in slot 0
+fn parse_md_member(token: &str) -> Option<MdMember> {
+    let (device, rest) = token.split_once('[')?;
+    let (role, flags) = rest.split_once(']')?;
+    Some(MdMember {
+        device: device.to_string(),
+        role: role.parse().ok()?,
+        failed: flags.contains("(F)"),
+        spare: flags.contains("(S)"),
+    })
+}
+
+// "[===>....]  recovery = 15.0% (157696/1047552) finish=0.5min speed=26282K/sec"
+// or "resync=DELAYED" / "resync=PENDING"
+fn parse_md_sync(line: &str) -> Option<MdSync> {
+    const ACTIONS: [&str; 4] = ["resync", "recovery", "check", "reshape"];
+
+    let action = ACTIONS.iter().find(|a| {
+        line.split_whitespace().any(|t| t == **a || t.starts_with(&format!("{}=", a)))
+    })?;
+    let progress_percent = line
+        .split_whitespace()
+        .find_map(|t| t.strip_suffix('%'))
+        .and_then(|v| v.parse().ok());
+    let field = |key: &str| {
+        line.split_whitespace()
+            .find_map(|t| t.strip_prefix(key))
+            .map(|v| v.to_string())
+    };
+
+    Some(MdSync {
+        action: action.to_string(),
+        progress_percent,
+        finish_minutes: field("finish=").and_then(|v| v.trim_end_matches("min").parse().ok()),
+        speed_kbps: field("speed=").and_then(|v| v.trim_end_matches("K/sec").parse().ok()),
+    })
+}
+
+/// Parse the contents of /proc/spl/kstat/zfs/arcstats.
+pub fn parse_arcstats(data: &str) -> ZfsArcStats {
+    // Two header lines, then "name type data" rows
+    let fields: std::collections::HashMap<&str, u64> = data
+        .lines()
+        .skip(2)
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let name = parts.next()?;
+            let value = parts.nth(1)?.parse().ok()?;
+            Some((name, value))
+        })
+        .collect();
+    let get = |key: &str| fields.get(key).copied().unwrap_or(0);
+
+    ZfsArcStats {
+        size: get("size"),
+        target_size: get("c"),
+        min_size: get("c_min"),
+        max_size: get("c_max"),
+        hits: get("hits"),
+        misses: get("misses"),
+        l2_hits: get("l2_hits"),
+        l2_misses: get("l2_misses"),
+        l2_size: get("l2_size"),
+        memory_throttle_count: get("memory_throttle_count"),
+        hit_percent: None,
+    }
+}
+
+/// Pool states from the per-pool kstat directories (OpenZFS 0.8+).
+pub fn collect_zfs_pools_from(kstat_zfs: &Path) -> Vec<ZfsPool> {
+    let Ok(entries) = std::fs::read_dir(kstat_zfs) else {
+        return Vec::new();
+    };
+    let mut pools: Vec<ZfsPool> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let state = std::fs::read_to_string(entry.path().join("state")).ok()?;
+            Some(ZfsPool {
+                name: entry.file_name().to_string_lossy().to_string(),
+                state: state.trim().to_string(),
+            })
+        })
+        .collect();
+    pools.sort_by(|a, b| a.name.cmp(&b.name));
+    pools
+}
+
+/// Device-mapper targets under a /sys/block style directory.
+pub fn collect_dm_devices_from(sys_block: &Path) -> Vec<DmDevice> {
+    use std::fs;
+
+    let Ok(entries) = fs::read_dir(sys_block) else {
+        return Vec::new();
+    };
+    let mut devices: Vec<DmDevice> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let device = entry.file_name().to_string_lossy().to_string();
+            if !device.starts_with("dm-") {
+                return None;
+            }
+            let dir = entry.path();
+            let read = |file: &str| fs::read_to_string(dir.join(file)).ok().map(|v| v.trim().to_string());
+
+            let name = read("dm/name").unwrap_or_default();
+            let uuid = read("dm/uuid").unwrap_or_default();
+            let kind = if uuid.starts_with("LVM-") {
+                DmKind::Lvm
+            } else if uuid.starts_with("CRYPT-") {
+                DmKind::Crypt
+            } else if uuid.starts_with("mpath-") {
+                DmKind::Multipath
+            } else {
+                DmKind::Other
+            };
+            let mut slaves: Vec<String> = fs::read_dir(dir.join("slaves"))
+                .map(|s| s.flatten().map(|e| e.file_name().to_string_lossy().to_string()).collect())
+                .unwrap_or_default();
+            slaves.sort();
+
+            Some(DmDevice {
+                lvm: (kind == DmKind::Lvm).then(|| split_lvm_name(&name)).flatten(),
+                suspended: read("dm/suspended").as_deref() == Some("1"),
+                device,
+                name,
+                uuid,
+                kind,
+                slaves,
+            })
+        })
+        .collect();
+    devices.sort_by(|a, b| a.device.cmp(&b.device));
+    devices
+}
+
+// dm names join VG and LV with a single '-' and double any '-' inside either name,
+// so "vg--data-lv--root" is VG "vg-data", LV "lv-root"
+fn split_lvm_name(name: &str) -> Option<LvmVolume> {
+    let bytes = name.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'-' {
+            if bytes.get(i + 1) == Some(&b'-') {
+                i += 2;
+                continue;
+            }
+            return Some(LvmVolume {
+                volume_group: name[..i].replace("--", "-"),
+                logical_volume: name[i + 1..].replace("--", "-"),
+            });
+        }
+        i += 1;
+    }
+    None
+}
+
+/// SMART health for one drive, from `smartctl --json`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DiskHealth {
+    pub device: String,   // As smartctl names it: "/dev/sda", "/dev/nvme0"
+    pub model: Option<String>,
+    pub serial: Option<String>,
+    pub passed: Option<bool>,   // Overall self-assessment; None when smartctl couldn't tell
+    pub temperature_celsius: Option<i64>,
+    pub power_on_hours: Option<u64>,
+    pub reallocated_sectors: Option<u64>,
+    pub pending_sectors: Option<u64>,
+    pub percentage_used: Option<u64>,   // NVMe wear estimate; can exceed 100
+    pub media_errors: Option<u64>,
+}
+
+/// Runs an external program and returns what it wrote to stdout.
+pub trait CommandRunner: Send + Sync {
+    fn run(&self, program: &str, args: &[&str]) -> std::io::Result<String>;
+}
+
+/// Runs programs on this machine, killing any that outlive `timeout`.
+pub struct SystemCommandRunner {
+    pub timeout: Duration,
+}
+
+impl CommandRunner for SystemCommandRunner {
+    fn run(&self, program: &str, args: &[&str]) -> std::io::Result<String> {
+        use std::io::Read;
+        use std::process::{Command, Stdio};
+
+        let mut child = Command::new(program)
+            .args(args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        // Drained on its own thread so a chatty child can't block on a full pipe
+        let mut stdout = child.stdout.take().expect("stdout is piped");
+        let reader = std::thread::spawn(move || {
+            let mut out = Vec::new();
+            stdout.read_to_end(&mut out).map(|_| out)
+        });
+
+        let deadline = Instant::now() + self.timeout;
+        while child.try_wait()?.is_none() {
+            if Instant::now() >= deadline {
+                let _ = child.kill();
+                // A process stuck in uninterruptible I/O on a dying drive only exits once
+                // the I/O does; reap it whenever that is rather than waiting here
+                std::thread::spawn(move || child.wait());
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::TimedOut,
+                    format!("{} timed out after {:?}", program, self.timeout),
+                ));
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+
+        // smartctl's exit status is a bitmask that is also non-zero for failing drives,
+        // so only the JSON on stdout matters
+        let out = reader
+            .join()
+            .map_err(|_| std::io::Error::other(format!("reading {} output", program)))??;
+        Ok(String::from_utf8_lossy(&out).into_owned())
+    }
+}
+
+/// Rate-limited SMART polling through a `CommandRunner`.
+pub struct SmartMonitor {
+    runner: Arc<dyn CommandRunner>,
+    interval: Duration,
+    background: bool,
+    last_run: Option<Instant>,
+    health: Arc<Mutex<Vec<DiskHealth>>>,
+}
+
+impl SmartMonitor {
+    /// smartctl on this machine: it can wake sleeping drives and takes a while per device,
+    /// so it runs in the background at most hourly, and is given up on after a minute.
+    pub fn system() -> Self {
+        let runner = SystemCommandRunner { timeout: Duration::from_secs(60) };
+        Self::new(Arc::new(runner), Duration::from_secs(3600), true)
+    }
+
+    pub fn new(runner: Arc<dyn CommandRunner>, interval: Duration, background: bool) -> Self {
+        Self {
+            runner,
+            interval,
+            background,
+            last_run: None,
+            health: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    // Latest results, starting a new query first if the last one is older than the interval.
+    // In background mode the new results show up on a later poll
+    pub fn poll(&mut self, now: Instant) -> Vec<DiskHealth> {
+        if !matches!(self.last_run, Some(t) if now.duration_since(t) < self.interval) {
+            self.last_run = Some(now);
+            let runner = self.runner.clone();
+            let health = self.health.clone();
+            let refresh = move || {
+                let results = query_smart(runner.as_ref());
+                // A refresh that panicked mid-update leaves nothing worth keeping
+                *health.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = results;
+            };
+            if self.background {
+                std::thread::spawn(refresh);
+            } else {
+                refresh();
+            }
+        }
+        self.health.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+    }
+}
+
+/// Query every drive `smartctl --scan` finds.
+pub fn query_smart(runner: &dyn CommandRunner) -> Vec<DiskHealth> {
+    let Ok(scan) = runner.run("smartctl", &["--scan", "--json"]) else {
+        return Vec::new();
+    };
+    parse_smartctl_scan(&scan)
+        .into_iter()
+        .filter_map(|(name, device_type)| {
+            let output = runner.run("smartctl", &["--json", "-a", "-d", &device_type, &name]).ok()?;
+            parse_smartctl_json(&output)
+        })
+        .collect()
+}
+
+/// Device names and types from `smartctl --scan --json`.
+pub fn parse_smartctl_scan(json: &str) -> Vec<(String, String)> {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(json) else {
+        return Vec::new();
+    };
+    value["devices"]
+        .as_array()
+        .map(|devices| {
+            devices
+                .iter()
+                .filter_map(|d| Some((d["name"].as_str()?.to_string(), d["type"].as_str()?.to_string())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Parse `smartctl --json -a` output; None when smartctl couldn't open the device.
+pub fn parse_smartctl_json(json: &str) -> Option<DiskHealth> {
+    let value: serde_json::Value = serde_json::from_str(json).ok()?;
+    let device = value["device"]["name"].as_str()?.to_string();
+    let nvme = &value["nvme_smart_health_information_log"];
+    let ata_raw = |id: u64| {
+        value["ata_smart_attributes"]["table"]
+            .as_array()?
+            .iter()
+            .find(|attr| attr["id"].as_u64() == Some(id))?["raw"]["value"]
+            .as_u64()
+    };
+
+    Some(DiskHealth {
+        device,
+        model: value["model_name"].as_str().map(String::from),
+        serial: value["serial_number"].as_str().map(String::from),
+        passed: value["smart_status"]["passed"].as_bool(),
+        temperature_celsius: value["temperature"]["current"].as_i64().or_else(|| nvme["temperature"].as_i64()),
+        power_on_hours: value["power_on_time"]["hours"].as_u64().or_else(|| nvme["power_on_hours"].as_u64()),
+        // ATA attribute 5 (Reallocated_Sector_Ct) and 197 (Current_Pending_Sector)
+        reallocated_sectors: ata_raw(5),
+        pending_sectors: ata_raw(197),
+        percentage_used: nvme["percentage_used"].as_u64(),
+        media_errors: nvme["media_errors"].as_u64(),
+    })
+}
+
+/// Set `health` on each filesystem from the SMART results for the drive behind it.
+pub fn attach_disk_health(root: &FsRoot, disks: &mut [DiskInfo], health: &[DiskHealth]) {
+    if health.is_empty() {
+        return;
+    }
+    for disk in disks.iter_mut() {
+        let Some(device) = disk.device.as_deref() else {
+            continue;
+        };
+        let drive = whole_disk(root, device);
+        disk.health = health.iter().find(|h| smart_device_matches(&h.device, &drive)).cloned();
+    }
+}
+
+// smartctl addresses NVMe drives by controller ("/dev/nvme0") while the block
+// devices are namespaces ("nvme0n1")
+fn smart_device_matches(smart_device: &str, drive: &str) -> bool {
+    let name = smart_device.trim_start_matches("/dev/");
+    drive == name || (name.starts_with("nvme") && drive.strip_prefix(name).is_some_and(|rest| rest.starts_with('n')))
+}
+
+// The whole-disk device for a partition ("sda1" -> "sda", "nvme0n1p2" -> "nvme0n1")
+#[cfg(target_os = "linux")]
+fn whole_disk(root: &FsRoot, device: &str) -> String {
+    let sys_path = root.sys("class/block").join(device);
+    if !sys_path.join("partition").exists() {
+        return device.to_string();
+    }
+    std::fs::canonicalize(&sys_path)
+        .ok()
+        .and_then(|path| path.parent()?.file_name().map(|n| n.to_string_lossy().to_string()))
+        .unwrap_or_else(|| device.to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn whole_disk(_root: &FsRoot, device: &str) -> String {
+    device.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_parse_mdstat_fixture() {
+        let arrays = parse_mdstat(include_str!("../../tests/fixtures/storage/mdstat_degraded"));
+        assert_eq!(arrays.len(), 4);
+
+        assert_eq!(arrays[0].level.as_deref(), Some("raid1"));
+        assert!(!arrays[0].degraded);
+
+        let md1 = &arrays[1];
+        assert!(md1.degraded);
+        assert_eq!((md1.total_devices, md1.active_devices), (Some(3), Some(2)));
+        assert_eq!(md1.member_status.as_deref(), Some("[_UU]"));
+        assert!(md1.members.iter().any(|m| m.device == "sdb2" && m.failed));
+        let sync = md1.sync.as_ref().unwrap();
+        assert_eq!(sync.action, "recovery");
+        assert_eq!(sync.progress_percent, Some(15.0));
+        assert_eq!(sync.finish_minutes, Some(0.5));
+        assert_eq!(sync.speed_kbps, Some(26282));
+
+        assert_eq!(arrays[2].state, "inactive");
+        assert_eq!(arrays[2].level, None);
+        assert!(arrays[2].members[0].spare);
+        assert!(!arrays[2].degraded);
+
+        assert!(arrays[3].read_only);
+        assert_eq!(arrays[3].sync.as_ref().map(|s| (s.action.as_str(), s.progress_percent)), Some(("resync", None)));
+    }
+
+    #[test]
+    fn test_parse_arcstats_fixture() {
+        let arc = parse_arcstats(include_str!("../../tests/fixtures/storage/arcstats"));
+        assert_eq!(arc.size, 4294967296);
+        assert_eq!(arc.max_size, 8589934592);
+        assert_eq!(arc.hits, 9000);
+        assert_eq!(arc.memory_throttle_count, 2);
+
+        let previous = ZfsArcStats { hits: 8000, misses: 0, ..Default::default() };
+        assert_eq!(arc.with_rates(&previous).hit_percent, Some(50.0));
+    }
+
+    #[test]
+    fn test_split_lvm_name() {
+        assert_eq!(
+            split_lvm_name("vg--data-lv--root"),
+            Some(LvmVolume { volume_group: "vg-data".to_string(), logical_volume: "lv-root".to_string() })
+        );
+        assert_eq!(split_lvm_name("novolume"), None);
+    }
+
+    #[test]
+    fn test_degraded_arrays_count() {
+        let root = std::env::temp_dir().join(format!("zek-storage-health-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let proc_root = root.join("proc");
+        let sys_root = root.join("sys");
+        fs::create_dir_all(proc_root.join("spl/kstat/zfs/tank")).unwrap();
+        fs::create_dir_all(proc_root.join("spl/kstat/zfs/backup")).unwrap();
+        fs::write(proc_root.join("mdstat"), include_str!("../../tests/fixtures/storage/mdstat_degraded")).unwrap();
+        fs::write(proc_root.join("spl/kstat/zfs/tank/state"), "DEGRADED\n").unwrap();
+        fs::write(proc_root.join("spl/kstat/zfs/backup/state"), "ONLINE\n").unwrap();
+        let dm = sys_root.join("block/dm-0");
+        fs::create_dir_all(dm.join("dm")).unwrap();
+        fs::create_dir_all(dm.join("slaves/sda3")).unwrap();
+        fs::write(dm.join("dm/name"), "vg0-root\n").unwrap();
+        fs::write(dm.join("dm/uuid"), "LVM-abc123\n").unwrap();
+        fs::write(dm.join("dm/suspended"), "0\n").unwrap();
+
+        let health = collect_storage_health_from(&proc_root, &sys_root).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        // md1 plus the DEGRADED pool
+        assert_eq!(health.degraded_arrays, 2);
+        assert_eq!(health.zfs_pools.len(), 2);
+        assert_eq!(health.dm_devices[0].kind, DmKind::Lvm);
+        assert_eq!(health.dm_devices[0].slaves, vec!["sda3"]);
+        assert_eq!(health.dm_devices[0].lvm.as_ref().unwrap().volume_group, "vg0");
+    }
+
+    struct FixtureRunner {
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    impl CommandRunner for FixtureRunner {
+        fn run(&self, _program: &str, args: &[&str]) -> std::io::Result<String> {
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let fixture = match args.last() {
+                Some(&"--json") => include_str!("../../tests/fixtures/smart/scan.json"),
+                Some(&"/dev/sda") => include_str!("../../tests/fixtures/smart/sata.json"),
+                Some(&"/dev/nvme0") => include_str!("../../tests/fixtures/smart/nvme.json"),
+                _ => include_str!("../../tests/fixtures/smart/no_permission.json"),
+            };
+            Ok(fixture.to_string())
+        }
+    }
+
+    #[test]
+    fn test_parse_smartctl_fixtures() {
+        let sata = parse_smartctl_json(include_str!("../../tests/fixtures/smart/sata.json")).unwrap();
+        assert_eq!(sata.passed, Some(true));
+        assert_eq!(sata.temperature_celsius, Some(34));
+        assert_eq!(sata.power_on_hours, Some(21873));
+        assert_eq!(sata.reallocated_sectors, Some(8));
+        assert_eq!(sata.pending_sectors, Some(2));
+        assert_eq!(sata.percentage_used, None);
+
+        let nvme = parse_smartctl_json(include_str!("../../tests/fixtures/smart/nvme.json")).unwrap();
+        assert_eq!(nvme.passed, Some(false));
+        assert_eq!(nvme.percentage_used, Some(103));
+        assert_eq!(nvme.media_errors, Some(17));
+        assert_eq!(nvme.reallocated_sectors, None);
+
+        assert_eq!(parse_smartctl_json(include_str!("../../tests/fixtures/smart/no_permission.json")), None);
+    }
+
+    #[test]
+    fn test_smart_monitor_is_rate_limited() {
+        let runner = Arc::new(FixtureRunner { calls: Default::default() });
+        let mut monitor = SmartMonitor::new(runner.clone(), Duration::from_secs(3600), false);
+        let start = Instant::now();
+
+        let health = monitor.poll(start);
+        assert_eq!(health.len(), 2);
+        // One scan plus one query per drive
+        assert_eq!(runner.calls.load(std::sync::atomic::Ordering::SeqCst), 3);
+
+        assert_eq!(monitor.poll(start + Duration::from_secs(600)).len(), 2);
+        assert_eq!(runner.calls.load(std::sync::atomic::Ordering::SeqCst), 3);
+
+        monitor.poll(start + Duration::from_secs(3601));
+        assert_eq!(runner.calls.load(std::sync::atomic::Ordering::SeqCst), 6);
+    }
+
+    #[test]
+    fn test_smart_monitor_survives_a_panicked_refresh() {
+        let runner = Arc::new(FixtureRunner { calls: Default::default() });
+        let mut monitor = SmartMonitor::new(runner.clone(), Duration::from_secs(3600), false);
+        let start = Instant::now();
+        assert_eq!(monitor.poll(start).len(), 2);
+
+        // A background refresh that panics while holding the lock poisons it
+        let health = monitor.health.clone();
+        let refresh = std::thread::spawn(move || {
+            let _guard = health.lock().unwrap();
+            panic!("refresh panicked mid-update");
+        });
+        assert!(refresh.join().is_err());
+
+        assert_eq!(monitor.poll(start + Duration::from_secs(600)).len(), 2);
+        assert_eq!(monitor.poll(start + Duration::from_secs(3601)).len(), 2);
+        assert_eq!(runner.calls.load(std::sync::atomic::Ordering::SeqCst), 6);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_system_runner_times_out() {
+        let runner = SystemCommandRunner { timeout: Duration::from_millis(200) };
+        let started = Instant::now();
+        let err = runner.run("sleep", &["10"]).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        assert_eq!(runner.run("echo", &["ok"]).unwrap(), "ok\n");
+    }
+
+    #[test]
+    fn test_smart_device_matching() {
+        assert!(smart_device_matches("/dev/sda", "sda"));
+        assert!(!smart_device_matches("/dev/sda", "sdab"));
+        assert!(smart_device_matches("/dev/nvme0", "nvme0n1"));
+        assert!(!smart_device_matches("/dev/nvme1", "nvme10n1"));
+    }
+}
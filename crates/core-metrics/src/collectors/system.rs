@@ -1,9 +1,339 @@
 use serde::{Serialize, Deserialize};
+use super::fsroot::FsRoot;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SystemInfo {
     pub name: String,
     pub kernel_version: String,
     pub os_version: String,
     pub host_name: String,
-}
\ No newline at end of file
+    #[serde(default)]
+    pub arch: String,
+    #[serde(default)]
+    pub cpu_model: String,
+    #[serde(default)]
+    pub uptime_secs: u64,
+    #[serde(default)]
+    pub boot_time: u64,   // Unix timestamp, seconds
+    // systemd-detect-virt style names ("kvm", "vmware", "docker", "lxc", ...)
+    #[serde(default)]
+    pub virtualization: Option<String>,
+    #[serde(default)]
+    pub container: Option<String>,
+    #[serde(default)]
+    pub logged_in_users: usize,   // Login sessions, from logind or else utmp
+    #[serde(default)]
+    pub process_count: usize,
+    #[serde(default)]
+    pub thread_count: usize,
+}
+
+/// VM and container detection, remembered per root: neither changes while zek runs, so
+/// it's only detected again when the collector is handed a different root.
+#[derive(Debug, Default)]
+pub struct DetectedVirtualization {
+    detected: Option<(FsRoot, Option<String>, Option<String>)>,
+}
+
+impl DetectedVirtualization {
+    // (virtualization, container) for the given root
+    pub fn get(&mut self, root: &FsRoot) -> (Option<String>, Option<String>) {
+        match &self.detected {
+            Some((detected_root, vm, container)) if detected_root == root => (vm.clone(), container.clone()),
+            _ => {
+                let (vm, container) = (detect_vm(root), detect_container(root));
+                self.detected = Some((root.clone(), vm.clone(), container.clone()));
+                (vm, container)
+            }
+        }
+    }
+}
+
+impl SystemInfo {
+    pub fn collect(sys: &sysinfo::System, root: &FsRoot, detected: &mut DetectedVirtualization) -> anyhow::Result<Self> {
+        let (virtualization, container) = detected.get(root);
+
+        // Counted from /proc so this doesn't depend on sysinfo's process list being refreshed.
+        // Elsewhere, sysinfo lists userland threads as processes too
+        let process_count = count_processes(root)?.unwrap_or_else(|| {
+            sys.processes()
+                .values()
+                .filter(|p| p.thread_kind() != Some(sysinfo::ThreadKind::Userland))
+                .count()
+        });
+        let thread_count = count_threads(root)?.unwrap_or(sys.processes().len());
+
+        Ok(Self {
+            name: sysinfo::System::name().unwrap_or_default(),
+            kernel_version: sysinfo::System::kernel_version().unwrap_or_default(),
+            os_version: sysinfo::System::long_os_version().unwrap_or_default(),
+            host_name: sysinfo::System::host_name().unwrap_or_default(),
+            arch: sysinfo::System::cpu_arch().unwrap_or_default(),
+            cpu_model: sys.cpus().first().map(|c| c.brand().trim().to_string()).unwrap_or_default(),
+            uptime_secs: sysinfo::System::uptime(),
+            boot_time: sysinfo::System::boot_time(),
+            virtualization,
+            container,
+            logged_in_users: count_logged_in_users(root),
+            process_count,
+            thread_count,
+        })
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn detect_vm(root: &FsRoot) -> Option<String> {
+    let dmi = |file: &str| root.read_sys(&format!("class/dmi/id/{}", file)).unwrap_or_default();
+    if let Some(vm) = vm_from_dmi(&dmi("sys_vendor"), &dmi("product_name"), &dmi("bios_vendor")) {
+        return Some(vm);
+    }
+    // Xen PV guests have no DMI data
+    if root.read_sys("hypervisor/type").is_ok_and(|t| t.trim() == "xen") {
+        return Some("xen".to_string());
+    }
+    // The CPU flag tells us we're virtualized, if not by what
+    let cpuinfo = root.read_proc("cpuinfo").unwrap_or_default();
+    let has_hypervisor_flag = cpuinfo
+        .lines()
+        .find(|l| l.starts_with("flags"))
+        .is_some_and(|l| l.split_whitespace().any(|f| f == "hypervisor"));
+    has_hypervisor_flag.then(|| "vm-other".to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn detect_vm(_root: &FsRoot) -> Option<String> {
+    None
+}
+
+/// Map DMI vendor/product strings to a systemd-detect-virt style hypervisor name.
+pub fn vm_from_dmi(sys_vendor: &str, product_name: &str, bios_vendor: &str) -> Option<String> {
+    const VENDORS: [(&str, &str); 12] = [
+        ("KVM", "kvm"),
+        ("OpenStack", "kvm"),
+        ("QEMU", "qemu"),
+        ("VMware", "vmware"),
+        ("VMW", "vmware"),
+        ("innotek GmbH", "oracle"),
+        ("VirtualBox", "oracle"),
+        ("Xen", "xen"),
+        ("Bochs", "bochs"),
+        ("Parallels", "parallels"),
+        ("BHYVE", "bhyve"),
+        ("Amazon EC2", "amazon"),
+    ];
+    let fields = [product_name.trim(), sys_vendor.trim(), bios_vendor.trim()];
+    for field in fields {
+        if let Some((_, name)) = VENDORS.iter().find(|(prefix, _)| field.starts_with(prefix)) {
+            return Some(name.to_string());
+        }
+    }
+    // Hyper-V reports Microsoft as the vendor of a "Virtual Machine"
+    if sys_vendor.trim() == "Microsoft Corporation" && product_name.trim() == "Virtual Machine" {
+        return Some("microsoft".to_string());
+    }
+    if sys_vendor.trim() == "Google" && product_name.trim() == "Google Compute Engine" {
+        return Some("google".to_string());
+    }
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn detect_container(root: &FsRoot) -> Option<String> {
+    use std::fs;
+
+    // Set by systemd, podman and LXC for PID 1 of the container
+    if let Ok(name) = fs::read_to_string(root.run("systemd/container")) {
+        return Some(name.trim().to_string());
+    }
+    if root.run(".containerenv").exists() {
+        return Some("podman".to_string());
+    }
+    if let Ok(environ) = fs::read(root.proc("1/environ")) {
+        let container = environ
+            .split(|b| *b == 0)
+            .find_map(|var| var.strip_prefix(b"container="))
+            .map(|v| String::from_utf8_lossy(v).to_string());
+        if container.is_some() {
+            return container;
+        }
+    }
+    if let Some(container) = root.read_proc("1/cgroup").ok().and_then(|c| container_from_cgroup(&c)) {
+        return Some(container);
+    }
+    // WSL behaves like a container around a shared kernel
+    let osrelease = root.read_proc("sys/kernel/osrelease").unwrap_or_default();
+    osrelease.to_lowercase().contains("microsoft").then(|| "wsl".to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn detect_container(_root: &FsRoot) -> Option<String> {
+    None
+}
+
+/// Guess the container runtime from PID 1's /proc/<pid>/cgroup (cgroup v1 hosts).
+pub fn container_from_cgroup(cgroup: &str) -> Option<String> {
+    cgroup.lines().find_map(|line| {
+        let path = line.splitn(3, ':').nth(2)?;
+        if path.contains("/kubepods") {
+            Some("kubernetes".to_string())
+        } else if path.contains("/docker") {
+            Some("docker".to_string())
+        } else if path.contains("/lxc") {
+            Some("lxc".to_string())
+        } else {
+            None
+        }
+    })
+}
+
+// logind's session files first: their format doesn't depend on the libc, and some
+// distributions no longer write utmp at all
+#[cfg(target_os = "linux")]
+fn count_logged_in_users(root: &FsRoot) -> usize {
+    match std::fs::read_dir(root.run("systemd/sessions")) {
+        Ok(entries) => entries
+            .flatten()
+            .filter(|e| !e.file_name().to_string_lossy().ends_with(".ref"))
+            .count(),
+        Err(_) => count_utmp_file(root),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn count_logged_in_users(_root: &FsRoot) -> usize {
+    0
+}
+
+#[cfg(all(target_os = "linux", target_env = "gnu", any(target_arch = "x86_64", target_arch = "aarch64")))]
+fn count_utmp_file(root: &FsRoot) -> usize {
+    std::fs::read(root.run("utmp")).map_or(0, |data| count_utmp_sessions(&data))
+}
+
+// musl's and 32-bit glibc's records are laid out differently
+#[cfg(all(target_os = "linux", not(all(target_env = "gnu", any(target_arch = "x86_64", target_arch = "aarch64")))))]
+fn count_utmp_file(_root: &FsRoot) -> usize {
+    0
+}
+
+// glibc's struct utmp on 64-bit targets: 384 bytes, starting with a 16-bit ut_type
+#[cfg(all(target_env = "gnu", any(target_arch = "x86_64", target_arch = "aarch64")))]
+const UTMP_RECORD_SIZE: usize = 384;
+#[cfg(all(target_env = "gnu", any(target_arch = "x86_64", target_arch = "aarch64")))]
+const UTMP_USER_PROCESS: i16 = 7;
+
+/// Count USER_PROCESS records in a utmp file.
+#[cfg(all(target_env = "gnu", any(target_arch = "x86_64", target_arch = "aarch64")))]
+pub fn count_utmp_sessions(data: &[u8]) -> usize {
+    data.chunks_exact(UTMP_RECORD_SIZE)
+        .filter(|record| i16::from_ne_bytes([record[0], record[1]]) == UTMP_USER_PROCESS)
+        .count()
+}
+
+#[cfg(target_os = "linux")]
+fn count_processes(root: &FsRoot) -> anyhow::Result<Option<usize>> {
+    use anyhow::Context;
+
+    let entries = std::fs::read_dir(&root.proc).with_context(|| format!("listing {}", root.proc.display()))?;
+    Ok(Some(
+        entries
+            .flatten()
+            .filter(|e| e.file_name().to_str().is_some_and(|n| n.bytes().all(|b| b.is_ascii_digit())))
+            .count(),
+    ))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn count_processes(_root: &FsRoot) -> anyhow::Result<Option<usize>> {
+    Ok(None)
+}
+
+// The kernel's count of scheduling entities: "0.98 0.70 0.57 2/71 669"
+#[cfg(target_os = "linux")]
+fn count_threads(root: &FsRoot) -> anyhow::Result<Option<usize>> {
+    let loadavg = root.require_proc("loadavg")?;
+    let threads = loadavg.split_whitespace().nth(3).and_then(|f| f.split_once('/')?.1.parse().ok());
+    match threads {
+        Some(threads) => Ok(Some(threads)),
+        None => anyhow::bail!("unexpected /proc/loadavg format: {:?}", loadavg.trim()),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn count_threads(_root: &FsRoot) -> anyhow::Result<Option<usize>> {
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vm_from_dmi() {
+        assert_eq!(vm_from_dmi("QEMU\n", "Standard PC (Q35 + ICH9, 2009)\n", "SeaBIOS\n").as_deref(), Some("qemu"));
+        assert_eq!(vm_from_dmi("Microsoft Corporation", "Virtual Machine", "Microsoft Corporation").as_deref(), Some("microsoft"));
+        assert_eq!(vm_from_dmi("Dell Inc.", "PowerEdge R740", "Dell Inc.").as_deref(), None);
+    }
+
+    #[test]
+    fn test_container_from_cgroup() {
+        let cgroup = "12:pids:/kubepods/besteffort/pod1234/abcdef\n0::/\n";
+        assert_eq!(container_from_cgroup(cgroup).as_deref(), Some("kubernetes"));
+        assert_eq!(container_from_cgroup("0::/init.scope\n"), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_detection_follows_the_root() {
+        let dir = std::env::temp_dir().join(format!("zek-detect-{}", std::process::id()));
+        let root = |name: &str, container: &str| {
+            let root = FsRoot::capture(&dir.join(name));
+            std::fs::create_dir_all(root.run("systemd")).unwrap();
+            std::fs::write(root.run("systemd/container"), format!("{}\n", container)).unwrap();
+            root
+        };
+        let (lxc, podman) = (root("lxc", "lxc"), root("podman", "podman"));
+
+        let mut detected = DetectedVirtualization::default();
+        let first = detected.get(&lxc).1;
+        let second = detected.get(&podman).1;
+        // Cached for the same root, even once the files change
+        std::fs::write(podman.run("systemd/container"), "docker\n").unwrap();
+        let cached = detected.get(&podman).1;
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(first.as_deref(), Some("lxc"));
+        assert_eq!(second.as_deref(), Some("podman"));
+        assert_eq!(cached.as_deref(), Some("podman"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_logind_sessions_preferred_over_utmp() {
+        let dir = std::env::temp_dir().join(format!("zek-sessions-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("run/systemd/sessions")).unwrap();
+        for session in ["3", "7", "7.ref"] {
+            std::fs::write(dir.join("run/systemd/sessions").join(session), "").unwrap();
+        }
+        // Five USER_PROCESS records, which must not be what's counted
+        let mut utmp = vec![0u8; 384 * 5];
+        for record in utmp.chunks_exact_mut(384) {
+            record[..2].copy_from_slice(&7i16.to_ne_bytes());
+        }
+        std::fs::write(dir.join("run/utmp"), utmp).unwrap();
+
+        let users = count_logged_in_users(&FsRoot::capture(&dir));
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(users, 2);
+    }
+
+    #[cfg(all(target_env = "gnu", any(target_arch = "x86_64", target_arch = "aarch64")))]
+    #[test]
+    fn test_count_utmp_sessions() {
+        let mut data = vec![0u8; UTMP_RECORD_SIZE * 3];
+        // BOOT_TIME, USER_PROCESS, USER_PROCESS
+        data[0..2].copy_from_slice(&2i16.to_ne_bytes());
+        data[UTMP_RECORD_SIZE..UTMP_RECORD_SIZE + 2].copy_from_slice(&7i16.to_ne_bytes());
+        data[UTMP_RECORD_SIZE * 2..UTMP_RECORD_SIZE * 2 + 2].copy_from_slice(&7i16.to_ne_bytes());
+        assert_eq!(count_utmp_sessions(&data), 2);
+    }
+}
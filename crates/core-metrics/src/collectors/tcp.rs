@@ -0,0 +1,241 @@
+use serde::{Serialize, Deserialize};
+use super::fsroot::FsRoot;
+#[cfg(target_os = "linux")]
+use std::collections::HashMap;
+
+/// System-wide TCP/UDP stack counters from /proc/net/snmp, /proc/net/netstat and /proc/net/sockstat.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TcpStats {
+    // Cumulative counters as reported by the kernel
+    pub active_opens: u64,
+    pub passive_opens: u64,
+    pub attempt_fails: u64,
+    pub estab_resets: u64,
+    pub out_rsts: u64,
+    pub out_segs: u64,
+    pub retrans_segs: u64,
+    pub listen_overflows: u64,
+    pub listen_drops: u64,
+    pub syncookies_sent: u64,
+    pub syncookies_recv: u64,
+    pub syncookies_failed: u64,
+    pub memory_pressures: u64,
+    pub udp_rcvbuf_errors: u64,
+    pub udp_sndbuf_errors: u64,
+    pub udp_in_errors: u64,
+    // Current values
+    pub curr_estab: u64,
+    pub memory_pages: u64,
+    pub memory_pressure_pages: u64,   // tcp_mem "pressure" threshold
+    pub under_memory_pressure: bool,
+    // Per-second rates, calculated when comparing with previous snapshots
+    pub rates: TcpRates,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TcpRates {
+    pub active_opens: f64,
+    pub passive_opens: f64,
+    pub attempt_fails: f64,
+    pub estab_resets: f64,
+    pub out_rsts: f64,
+    pub retrans_segs: f64,
+    pub retrans_percent: f64,   // Retransmitted share of outgoing segments
+    pub listen_overflows: f64,
+    pub listen_drops: f64,
+    pub syncookies_sent: f64,
+    pub syncookies_failed: f64,
+    pub memory_pressures: f64,
+    pub udp_rcvbuf_errors: f64,
+    pub udp_sndbuf_errors: f64,
+    pub udp_in_errors: f64,
+}
+
+impl TcpStats {
+    // Calculate per-second rates based on previous snapshot
+    pub fn with_rates(mut self, previous: &TcpStats, time_delta_ms: f64) -> Self {
+        if time_delta_ms <= 0.0 {
+            return self;
+        }
+        let secs = time_delta_ms / 1000.0;
+        let rate = |now: u64, before: u64| now.saturating_sub(before) as f64 / secs;
+
+        let out_segs = self.out_segs.saturating_sub(previous.out_segs);
+        let retrans = self.retrans_segs.saturating_sub(previous.retrans_segs);
+
+        self.rates = TcpRates {
+            active_opens: rate(self.active_opens, previous.active_opens),
+            passive_opens: rate(self.passive_opens, previous.passive_opens),
+            attempt_fails: rate(self.attempt_fails, previous.attempt_fails),
+            estab_resets: rate(self.estab_resets, previous.estab_resets),
+            out_rsts: rate(self.out_rsts, previous.out_rsts),
+            retrans_segs: rate(self.retrans_segs, previous.retrans_segs),
+            retrans_percent: if out_segs > 0 {
+                retrans as f64 / out_segs as f64 * 100.0
+            } else {
+                0.0
+            },
+            listen_overflows: rate(self.listen_overflows, previous.listen_overflows),
+            listen_drops: rate(self.listen_drops, previous.listen_drops),
+            syncookies_sent: rate(self.syncookies_sent, previous.syncookies_sent),
+            syncookies_failed: rate(self.syncookies_failed, previous.syncookies_failed),
+            memory_pressures: rate(self.memory_pressures, previous.memory_pressures),
+            udp_rcvbuf_errors: rate(self.udp_rcvbuf_errors, previous.udp_rcvbuf_errors),
+            udp_sndbuf_errors: rate(self.udp_sndbuf_errors, previous.udp_sndbuf_errors),
+            udp_in_errors: rate(self.udp_in_errors, previous.udp_in_errors),
+        };
+
+        self
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn collect_tcp_stats(root: &FsRoot) -> anyhow::Result<Option<TcpStats>> {
+    // Absent on kernels built without networking
+    let Some(snmp) = root.optional_proc("net/snmp")? else {
+        return Ok(None);
+    };
+    let snmp = parse_snmp_table(&snmp);
+    let netstat = root.optional_proc("net/netstat")?
+        .map(|data| parse_snmp_table(&data))
+        .unwrap_or_default();
+
+    let get = |table: &HashMap<String, HashMap<String, u64>>, section: &str, key: &str| {
+        table.get(section).and_then(|s| s.get(key)).copied().unwrap_or(0)
+    };
+
+    let mut stats = TcpStats {
+        active_opens: get(&snmp, "Tcp", "ActiveOpens"),
+        passive_opens: get(&snmp, "Tcp", "PassiveOpens"),
+        attempt_fails: get(&snmp, "Tcp", "AttemptFails"),
+        estab_resets: get(&snmp, "Tcp", "EstabResets"),
+        out_rsts: get(&snmp, "Tcp", "OutRsts"),
+        out_segs: get(&snmp, "Tcp", "OutSegs"),
+        retrans_segs: get(&snmp, "Tcp", "RetransSegs"),
+        curr_estab: get(&snmp, "Tcp", "CurrEstab"),
+        udp_rcvbuf_errors: get(&snmp, "Udp", "RcvbufErrors"),
+        udp_sndbuf_errors: get(&snmp, "Udp", "SndbufErrors"),
+        udp_in_errors: get(&snmp, "Udp", "InErrors"),
+        listen_overflows: get(&netstat, "TcpExt", "ListenOverflows"),
+        listen_drops: get(&netstat, "TcpExt", "ListenDrops"),
+        syncookies_sent: get(&netstat, "TcpExt", "SyncookiesSent"),
+        syncookies_recv: get(&netstat, "TcpExt", "SyncookiesRecv"),
+        syncookies_failed: get(&netstat, "TcpExt", "SyncookiesFailed"),
+        memory_pressures: get(&netstat, "TcpExt", "TCPMemoryPressures"),
+        ..Default::default()
+    };
+
+    // "TCP: inuse 4 orphan 0 tw 0 alloc 4 mem 0" - mem is in pages
+    if let Ok(sockstat) = root.read_proc("net/sockstat") {
+        if let Some(line) = sockstat.lines().find(|l| l.starts_with("TCP:")) {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if let Some(pos) = fields.iter().position(|f| *f == "mem") {
+                stats.memory_pages = fields.get(pos + 1).and_then(|v| v.parse().ok()).unwrap_or(0);
+            }
+        }
+    }
+    // tcp_mem is "min pressure max" in pages
+    if let Ok(tcp_mem) = root.read_proc("sys/net/ipv4/tcp_mem") {
+        stats.memory_pressure_pages = tcp_mem
+            .split_whitespace()
+            .nth(1)
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0);
+    }
+    stats.under_memory_pressure =
+        stats.memory_pressure_pages > 0 && stats.memory_pages >= stats.memory_pressure_pages;
+
+    Ok(Some(stats))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn collect_tcp_stats(_root: &FsRoot) -> anyhow::Result<Option<TcpStats>> {
+    Ok(None)
+}
+
+// /proc/net/snmp and /proc/net/netstat come as pairs of lines per section:
+//   Tcp: ActiveOpens PassiveOpens ...
+//   Tcp: 8 6 ...
+#[cfg(target_os = "linux")]
+fn parse_snmp_table(data: &str) -> HashMap<String, HashMap<String, u64>> {
+    let mut table = HashMap::new();
+    let mut lines = data.lines();
+
+    while let (Some(header), Some(values)) = (lines.next(), lines.next()) {
+        let mut header = header.split_whitespace();
+        let mut values = values.split_whitespace();
+        let (Some(section), Some(_)) = (header.next(), values.next()) else {
+            continue;
+        };
+        let section = section.trim_end_matches(':').to_string();
+        let entries: HashMap<String, u64> = header
+            .zip(values)
+            // Some fields (e.g. Tcp MaxConn) can be -1
+            .filter_map(|(k, v)| v.parse::<u64>().ok().map(|v| (k.to_string(), v)))
+            .collect();
+        table.insert(section, entries);
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_parse_snmp_table() {
+        let data = "\
+Ip: Forwarding DefaultTTL InReceives
+Ip: 1 64 2281961
+Tcp: RtoAlgorithm RtoMin RtoMax MaxConn ActiveOpens PassiveOpens AttemptFails EstabResets CurrEstab InSegs OutSegs RetransSegs InErrs OutRsts InCsumErrors
+Tcp: 1 200 120000 -1 31087 1412 2104 1015 19 2167812 2287563 3480 3 14077 0
+Udp: InDatagrams NoPorts InErrors OutDatagrams RcvbufErrors SndbufErrors InCsumErrors IgnoredMulti MemErrors
+Udp: 61434 1043 12 64177 7 2 0 3201 0
+";
+        let table = parse_snmp_table(data);
+        assert_eq!(table.len(), 3);
+        let tcp = &table["Tcp"];
+        assert_eq!(tcp["ActiveOpens"], 31087);
+        assert_eq!(tcp["RetransSegs"], 3480);
+        assert_eq!(tcp["CurrEstab"], 19);
+        // MaxConn is -1 and can't be a counter
+        assert!(!tcp.contains_key("MaxConn"));
+        let udp = &table["Udp"];
+        assert_eq!((udp["InErrors"], udp["RcvbufErrors"], udp["SndbufErrors"]), (12, 7, 2));
+    }
+
+    #[test]
+    fn test_rates() {
+        let before = TcpStats {
+            active_opens: 100,
+            out_segs: 10_000,
+            retrans_segs: 50,
+            listen_drops: 4,
+            udp_in_errors: 900,
+            ..Default::default()
+        };
+        let after = TcpStats {
+            active_opens: 120,
+            out_segs: 12_000,
+            retrans_segs: 70,
+            listen_drops: 4,
+            // The counter was reset (e.g. the network namespace was recreated)
+            udp_in_errors: 3,
+            ..Default::default()
+        };
+
+        let rates = after.with_rates(&before, 2000.0).rates;
+        assert_eq!(rates.active_opens, 10.0);
+        assert_eq!(rates.retrans_segs, 10.0);
+        assert_eq!(rates.retrans_percent, 1.0);
+        assert_eq!(rates.listen_drops, 0.0);
+        // A reset reads as no activity, not a huge wrapped rate
+        assert_eq!(rates.udp_in_errors, 0.0);
+
+        // No interval, no rates
+        let unchanged = before.clone().with_rates(&before, 0.0).rates;
+        assert_eq!(unchanged.active_opens, 0.0);
+    }
+}
@@ -0,0 +1,246 @@
+use serde::{Serialize, Deserialize};
+use super::fsroot::FsRoot;
+use std::collections::HashMap;
+
+/// Memory breakdown from /proc/meminfo. Sizes are in bytes, hugepage counts in pages.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MemoryStats {
+    pub total: u64,
+    pub free: u64,
+    pub available: u64,
+    pub buffers: u64,
+    pub cached: u64,
+    pub swap_cached: u64,
+    #[serde(default)]
+    pub swap_total: u64,
+    #[serde(default)]
+    pub swap_free: u64,
+    pub active: u64,
+    pub inactive: u64,
+    pub anon_pages: u64,
+    pub mapped: u64,
+    pub shmem: u64,
+    pub slab: u64,
+    pub slab_reclaimable: u64,
+    pub slab_unreclaimable: u64,
+    pub page_tables: u64,
+    pub dirty: u64,
+    pub writeback: u64,
+    pub commit_limit: u64,
+    pub committed_as: u64,
+    pub anon_huge_pages: u64,
+    pub hugepages_total: u64,
+    pub hugepages_free: u64,
+    pub hugepages_reserved: u64,
+    pub hugepages_surplus: u64,
+    pub hugepage_size: u64,
+}
+
+/// Paging and reclaim counters from /proc/vmstat.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VmStats {
+    // Cumulative counters as reported by the kernel
+    pub pgfault: u64,
+    pub pgmajfault: u64,
+    pub pswpin: u64,
+    pub pswpout: u64,
+    pub pgscan_kswapd: u64,
+    pub pgscan_direct: u64,
+    pub pgsteal_kswapd: u64,
+    pub pgsteal_direct: u64,
+    pub allocstall: u64,
+    pub oom_kill: u64,
+    // Per-second rates, calculated when comparing with previous snapshots
+    pub rates: VmRates,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VmRates {
+    pub pgfault: f64,
+    pub pgmajfault: f64,
+    pub pswpin: f64,
+    pub pswpout: f64,
+    pub pgscan_kswapd: f64,
+    pub pgscan_direct: f64,
+    pub pgsteal_kswapd: f64,
+    pub pgsteal_direct: f64,
+    pub allocstall: f64,
+    pub oom_kill: f64,
+    // Pages reclaimed per page scanned; low values under heavy scanning mean reclaim is struggling
+    pub reclaim_efficiency: Option<f64>,
+}
+
+impl VmStats {
+    // Calculate per-second rates based on previous snapshot
+    pub fn with_rates(mut self, previous: &VmStats, time_delta_ms: f64) -> Self {
+        if time_delta_ms <= 0.0 {
+            return self;
+        }
+        let secs = time_delta_ms / 1000.0;
+        let rate = |now: u64, before: u64| now.saturating_sub(before) as f64 / secs;
+
+        let scanned = (self.pgscan_kswapd + self.pgscan_direct)
+            .saturating_sub(previous.pgscan_kswapd + previous.pgscan_direct);
+        let stolen = (self.pgsteal_kswapd + self.pgsteal_direct)
+            .saturating_sub(previous.pgsteal_kswapd + previous.pgsteal_direct);
+
+        self.rates = VmRates {
+            pgfault: rate(self.pgfault, previous.pgfault),
+            pgmajfault: rate(self.pgmajfault, previous.pgmajfault),
+            pswpin: rate(self.pswpin, previous.pswpin),
+            pswpout: rate(self.pswpout, previous.pswpout),
+            pgscan_kswapd: rate(self.pgscan_kswapd, previous.pgscan_kswapd),
+            pgscan_direct: rate(self.pgscan_direct, previous.pgscan_direct),
+            pgsteal_kswapd: rate(self.pgsteal_kswapd, previous.pgsteal_kswapd),
+            pgsteal_direct: rate(self.pgsteal_direct, previous.pgsteal_direct),
+            allocstall: rate(self.allocstall, previous.allocstall),
+            oom_kill: rate(self.oom_kill, previous.oom_kill),
+            reclaim_efficiency: (scanned > 0).then(|| stolen as f64 / scanned as f64),
+        };
+
+        self
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn collect_memory_stats(root: &FsRoot) -> anyhow::Result<Option<MemoryStats>> {
+    Ok(Some(parse_meminfo(&root.require_proc("meminfo")?)))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn collect_memory_stats(_root: &FsRoot) -> anyhow::Result<Option<MemoryStats>> {
+    Ok(None)
+}
+
+#[cfg(target_os = "linux")]
+pub fn collect_vm_stats(root: &FsRoot) -> anyhow::Result<Option<VmStats>> {
+    Ok(Some(parse_vmstat(&root.require_proc("vmstat")?)))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn collect_vm_stats(_root: &FsRoot) -> anyhow::Result<Option<VmStats>> {
+    Ok(None)
+}
+
+/// Parse the contents of /proc/meminfo.
+pub fn parse_meminfo(data: &str) -> MemoryStats {
+    // Lines look like "Dirty:               480 kB" or "HugePages_Total:       0"
+    let fields: HashMap<&str, u64> = data
+        .lines()
+        .filter_map(|line| {
+            let (key, rest) = line.split_once(':')?;
+            let mut parts = rest.split_whitespace();
+            let value: u64 = parts.next()?.parse().ok()?;
+            let value = match parts.next() {
+                Some("kB") => value * 1024,
+                _ => value,
+            };
+            Some((key, value))
+        })
+        .collect();
+    let get = |key: &str| fields.get(key).copied().unwrap_or(0);
+
+    MemoryStats {
+        total: get("MemTotal"),
+        free: get("MemFree"),
+        available: get("MemAvailable"),
+        buffers: get("Buffers"),
+        cached: get("Cached"),
+        swap_cached: get("SwapCached"),
+        swap_total: get("SwapTotal"),
+        swap_free: get("SwapFree"),
+        active: get("Active"),
+        inactive: get("Inactive"),
+        anon_pages: get("AnonPages"),
+        mapped: get("Mapped"),
+        shmem: get("Shmem"),
+        slab: get("Slab"),
+        slab_reclaimable: get("SReclaimable"),
+        slab_unreclaimable: get("SUnreclaim"),
+        page_tables: get("PageTables"),
+        dirty: get("Dirty"),
+        writeback: get("Writeback"),
+        commit_limit: get("CommitLimit"),
+        committed_as: get("Committed_AS"),
+        anon_huge_pages: get("AnonHugePages"),
+        hugepages_total: get("HugePages_Total"),
+        hugepages_free: get("HugePages_Free"),
+        hugepages_reserved: get("HugePages_Rsvd"),
+        hugepages_surplus: get("HugePages_Surp"),
+        hugepage_size: get("Hugepagesize"),
+    }
+}
+
+/// Parse the contents of /proc/vmstat.
+pub fn parse_vmstat(data: &str) -> VmStats {
+    let fields: HashMap<&str, u64> = data
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(' ')?;
+            Some((key, value.trim().parse().ok()?))
+        })
+        .collect();
+    let get = |key: &str| fields.get(key).copied().unwrap_or(0);
+
+    // Kernels before 4.8 split scan/steal/allocstall counters per zone
+    // (pgscan_kswapd_normal, pgscan_direct_dma32, ...), newer ones report a single total
+    let sum = |prefix: &str| -> u64 {
+        fields
+            .iter()
+            .filter(|(key, _)| {
+                key.strip_prefix(prefix)
+                    .is_some_and(|rest| rest.is_empty() || (rest.starts_with('_') && rest != "_throttle"))
+            })
+            .map(|(_, v)| *v)
+            .sum()
+    };
+
+    VmStats {
+        pgfault: get("pgfault"),
+        pgmajfault: get("pgmajfault"),
+        pswpin: get("pswpin"),
+        pswpout: get("pswpout"),
+        pgscan_kswapd: sum("pgscan_kswapd"),
+        pgscan_direct: sum("pgscan_direct"),
+        pgsteal_kswapd: sum("pgsteal_kswapd"),
+        pgsteal_direct: sum("pgsteal_direct"),
+        allocstall: sum("allocstall"),
+        oom_kill: get("oom_kill"),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_meminfo_units() {
+        let data = "MemTotal:        6158152 kB\nDirty:               480 kB\nHugePages_Total:       4\nHugepagesize:       2048 kB\n";
+        let mem = parse_meminfo(data);
+        assert_eq!(mem.total, 6158152 * 1024);
+        assert_eq!(mem.dirty, 480 * 1024);
+        assert_eq!(mem.hugepages_total, 4);
+        assert_eq!(mem.hugepage_size, 2048 * 1024);
+    }
+
+    #[test]
+    fn test_parse_vmstat_sums_per_zone_counters() {
+        let data = "pgscan_kswapd_dma32 10\npgscan_kswapd_normal 30\npgscan_direct_normal 5\npgscan_direct_throttle 99\nallocstall_normal 2\nallocstall_movable 1\noom_kill 1\n";
+        let vm = parse_vmstat(data);
+        assert_eq!(vm.pgscan_kswapd, 40);
+        assert_eq!(vm.pgscan_direct, 5);
+        assert_eq!(vm.allocstall, 3);
+        assert_eq!(vm.oom_kill, 1);
+    }
+
+    #[test]
+    fn test_vmstat_rates() {
+        let previous = VmStats { pgmajfault: 100, pgscan_direct: 1000, pgsteal_direct: 500, ..Default::default() };
+        let current = VmStats { pgmajfault: 300, pgscan_direct: 3000, pgsteal_direct: 1000, ..Default::default() };
+        let rates = current.with_rates(&previous, 2000.0).rates;
+        assert_eq!(rates.pgmajfault, 100.0);
+        assert_eq!(rates.pgscan_direct, 1000.0);
+        assert_eq!(rates.reclaim_efficiency, Some(0.25));
+    }
+}
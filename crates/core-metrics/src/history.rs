@@ -0,0 +1,293 @@
+use crate::cfg;
+use crate::collectors::snapshot::Snapshot;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::Mutex;
+
+/// How finely a stretch of history is sampled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Resolution {
+    #[serde(rename = "raw")]
+    Raw,          // One point per tick
+    #[serde(rename = "10s")]
+    TenSeconds,
+    #[serde(rename = "1m")]
+    OneMinute,
+}
+
+impl Resolution {
+    fn bucket_ms(self) -> Option<i64> {
+        match self {
+            Resolution::Raw => None,
+            Resolution::TenSeconds => Some(10_000),
+            Resolution::OneMinute => Some(60_000),
+        }
+    }
+}
+
+/// One point of a series. Raw points have min == avg == max.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Point {
+    pub ts: i64,   // Unix timestamp, milliseconds; start of the bucket for rollups
+    pub min: f64,
+    pub avg: f64,
+    pub max: f64,
+}
+
+/// Every metric over a requested range, taken from a single tier.
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryRange {
+    pub resolution: Resolution,
+    pub series: BTreeMap<String, Vec<Point>>,
+}
+
+// A rollup bucket still taking samples
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    start: i64,
+    min: f64,
+    max: f64,
+    sum: f64,
+    count: u32,
+}
+
+impl Bucket {
+    fn point(&self) -> Point {
+        Point { ts: self.start, min: self.min, avg: self.sum / self.count as f64, max: self.max }
+    }
+}
+
+#[derive(Default)]
+struct Series {
+    points: VecDeque<Point>,
+    pending: Option<Bucket>,
+}
+
+struct Tier {
+    resolution: Resolution,
+    retention_ms: i64,
+    series: HashMap<String, Series>,
+}
+
+impl Tier {
+    fn new(resolution: Resolution, retention_ms: i64) -> Self {
+        Self { resolution, retention_ms, series: HashMap::new() }
+    }
+
+    fn add(&mut self, ts: i64, metrics: &HashMap<String, f64>) {
+        for (name, &value) in metrics {
+            let series = self.series.entry(name.clone()).or_default();
+            let Some(bucket_ms) = self.resolution.bucket_ms() else {
+                series.points.push_back(Point { ts, min: value, avg: value, max: value });
+                continue;
+            };
+            let start = ts - ts.rem_euclid(bucket_ms);
+            match &mut series.pending {
+                Some(b) if b.start == start => {
+                    b.min = b.min.min(value);
+                    b.max = b.max.max(value);
+                    b.sum += value;
+                    b.count += 1;
+                }
+                pending => {
+                    if let Some(done) = pending.take() {
+                        series.points.push_back(done.point());
+                    }
+                    *pending = Some(Bucket { start, min: value, max: value, sum: value, count: 1 });
+                }
+            }
+        }
+
+        let cutoff = ts - self.retention_ms;
+        for series in self.series.values_mut() {
+            while matches!(series.points.front(), Some(p) if p.ts < cutoff) {
+                series.points.pop_front();
+            }
+        }
+        // Metrics that stopped being reported eventually age out entirely
+        self.series.retain(|_, s| !s.points.is_empty() || matches!(s.pending, Some(b) if b.start >= cutoff));
+    }
+
+    fn range(&self, since: i64) -> HistoryRange {
+        // A rollup that started before `since` but ends after it still covers part of the range
+        let in_range = |p: &Point| match self.resolution.bucket_ms() {
+            Some(bucket_ms) => p.ts + bucket_ms > since,
+            None => p.ts >= since,
+        };
+        let series = self
+            .series
+            .iter()
+            .map(|(name, s)| {
+                // The bucket still filling up is included so the newest data isn't missing
+                let points = s.points.iter().copied().chain(s.pending.map(|b| b.point()));
+                (name.clone(), points.filter(in_range).collect())
+            })
+            .collect();
+        HistoryRange { resolution: self.resolution, series }
+    }
+}
+
+struct Tiers {
+    raw: Tier,
+    ten_seconds: Tier,
+    one_minute: Tier,
+}
+
+/// Metric history kept at three resolutions: every tick for the last few minutes,
+/// 10 s min/avg/max rollups for a day and 1 min rollups for a week by default.
+/// Only numeric series are kept, not whole snapshots: everything in `Snapshot::metrics()`
+/// plus per-core CPU, per-interface throughput and per-disk I/O.
+pub struct HistoricalData {
+    tiers: Mutex<Tiers>,
+}
+
+impl HistoricalData {
+    pub fn new(cfg: &cfg::History) -> Self {
+        let minutes = |m: u64| m as i64 * 60_000;
+        Self {
+            tiers: Mutex::new(Tiers {
+                raw: Tier::new(Resolution::Raw, minutes(cfg.raw_minutes)),
+                ten_seconds: Tier::new(Resolution::TenSeconds, minutes(cfg.rollup_10s_hours * 60)),
+                one_minute: Tier::new(Resolution::OneMinute, minutes(cfg.rollup_1m_days * 24 * 60)),
+            }),
+        }
+    }
+
+    pub fn add_snapshot(&self, snapshot: &Snapshot) {
+        let metrics = series_values(snapshot);
+        let mut tiers = self.tiers.lock().unwrap();
+        tiers.raw.add(snapshot.ts, &metrics);
+        tiers.ten_seconds.add(snapshot.ts, &metrics);
+        tiers.one_minute.add(snapshot.ts, &metrics);
+    }
+
+    /// The last `duration_secs` of history from the finest tier that covers all of it.
+    pub fn get_recent_data(&self, duration_secs: u64) -> HistoryRange {
+        self.range_at(chrono::Utc::now().timestamp_millis(), duration_secs)
+    }
+
+    fn range_at(&self, now_ms: i64, duration_secs: u64) -> HistoryRange {
+        let wanted_ms = duration_secs as i64 * 1000;
+        let tiers = self.tiers.lock().unwrap();
+        let tier = [&tiers.raw, &tiers.ten_seconds]
+            .into_iter()
+            .find(|t| wanted_ms <= t.retention_ms)
+            .unwrap_or(&tiers.one_minute);
+        tier.range(now_ms - wanted_ms)
+    }
+}
+
+// Per-instance series are named "<metric>.<instance>", e.g. "cpu_usage.cpu3"
+fn series_values(snapshot: &Snapshot) -> HashMap<String, f64> {
+    let mut values = snapshot.metrics();
+    for (i, usage) in snapshot.cpu_per_core.iter().enumerate() {
+        values.insert(format!("cpu_usage.cpu{}", i), *usage as f64);
+    }
+    for net in &snapshot.network {
+        values.insert(format!("net_rx_bytes_per_sec.{}", net.interface), net.rx_throughput);
+        values.insert(format!("net_tx_bytes_per_sec.{}", net.interface), net.tx_throughput);
+    }
+    for io in &snapshot.disk_io {
+        values.insert(format!("disk_read_bytes_per_sec.{}", io.device), io.read_bytes_per_sec);
+        values.insert(format!("disk_write_bytes_per_sec.{}", io.device), io.write_bytes_per_sec);
+        values.insert(format!("disk_util_percent.{}", io.device), io.util_percent);
+    }
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history() -> HistoricalData {
+        HistoricalData::new(&cfg::History { raw_minutes: 1, rollup_10s_hours: 1, rollup_1m_days: 1 })
+    }
+
+    fn add(history: &HistoricalData, ts: i64, cpu: f64) {
+        history.add_snapshot(&Snapshot { ts, cpu_total_pct: cpu, ..Default::default() });
+    }
+
+    #[test]
+    fn test_rollups_keep_min_avg_max() {
+        let history = history();
+        // 500 ms ticks over 25 s: cpu climbs 0, 1, 2, ...
+        for i in 0..50 {
+            add(&history, i * 500, i as f64);
+        }
+        let now = 24_500;
+
+        let raw = history.range_at(now, 60);
+        assert_eq!(raw.resolution, Resolution::Raw);
+        assert_eq!(raw.series["cpu_usage"].len(), 50);
+
+        let rolled = history.range_at(now, 3600);
+        assert_eq!(rolled.resolution, Resolution::TenSeconds);
+        let cpu = &rolled.series["cpu_usage"];
+        assert_eq!(cpu.len(), 3);
+        assert_eq!(cpu[0], Point { ts: 0, min: 0.0, avg: 9.5, max: 19.0 });
+        assert_eq!(cpu[1], Point { ts: 10_000, min: 20.0, avg: 29.5, max: 39.0 });
+        // The bucket still filling up
+        assert_eq!(cpu[2], Point { ts: 20_000, min: 40.0, avg: 44.5, max: 49.0 });
+
+        let long = history.range_at(now, 86_400);
+        assert_eq!(long.resolution, Resolution::OneMinute);
+        assert_eq!(long.series["cpu_usage"], vec![Point { ts: 0, min: 0.0, avg: 24.5, max: 49.0 }]);
+    }
+
+    #[test]
+    fn test_tiers_expire_independently() {
+        let history = history();
+        // One sample a second for two minutes
+        for s in 0..120 {
+            add(&history, s * 1000, 1.0);
+        }
+        let now = 119_000;
+
+        // Raw only holds the last minute
+        let raw = &history.range_at(now, 60).series["cpu_usage"];
+        assert_eq!(raw.first().unwrap().ts, 59_000);
+        // The 10 s tier still has the whole two minutes
+        let rolled = &history.range_at(now, 120).series["cpu_usage"];
+        assert_eq!(rolled.first().unwrap().ts, 0);
+        assert_eq!(rolled.len(), 12);
+        // A range narrower than the tier is trimmed to fit
+        assert_eq!(history.range_at(now, 30).series["cpu_usage"].len(), 31);
+    }
+
+    #[test]
+    fn test_range_keeps_straddling_bucket() {
+        let history = history();
+        for s in 0..100 {
+            add(&history, s * 1000, s as f64);
+        }
+
+        // 95 s back from 179 s starts at 84 s, partway through the 80 s bucket
+        let rolled = &history.range_at(179_000, 95).series["cpu_usage"];
+        assert_eq!(rolled.first().unwrap().ts, 80_000);
+        assert_eq!(rolled.len(), 2);
+        // A bucket that ended exactly where the range starts is left out
+        let rolled = &history.range_at(180_000, 90).series["cpu_usage"];
+        assert_eq!(rolled.first().unwrap().ts, 90_000);
+    }
+
+    #[test]
+    fn test_per_instance_series() {
+        use crate::collectors::{disk::DiskIoInfo, net::NetworkInfo};
+
+        let history = history();
+        let network = NetworkInfo { interface: "eth0".to_string(), rx_throughput: 1500.0, ..Default::default() };
+        let disk_io = DiskIoInfo { device: "nvme0n1".to_string(), util_percent: 42.0, ..Default::default() };
+        history.add_snapshot(&Snapshot {
+            ts: 0,
+            cpu_per_core: vec![10.0, 90.0],
+            network: vec![network],
+            disk_io: vec![disk_io],
+            ..Default::default()
+        });
+
+        let series = history.range_at(0, 60).series;
+        assert_eq!(series["cpu_usage.cpu1"][0].avg, 90.0);
+        assert_eq!(series["net_rx_bytes_per_sec.eth0"][0].avg, 1500.0);
+        assert_eq!(series["disk_util_percent.nvme0n1"][0].avg, 42.0);
+    }
+}
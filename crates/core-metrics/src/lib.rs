@@ -1,82 +1,59 @@
 mod cfg; 
 mod ringbuf; 
+pub mod bus;
+pub mod history;
 pub mod collectors;
 
-use crossbeam_channel::{unbounded, Receiver};
 use collectors::snapshot::Snapshot;
-use std::sync::{Arc, Mutex};
+use bus::{SnapshotBus, SnapshotReceiver};
+pub use history::{HistoricalData, HistoryRange};
+use std::sync::Arc;
 use tracing::debug;
-use std::collections::VecDeque;
 use std::time::Duration;
 
-// Historical data storage
-pub struct HistoricalData {
-    data_points: Arc<Mutex<VecDeque<Snapshot>>>,
-    max_points: usize,
-}
-
-impl HistoricalData {
-    pub fn new(max_points: usize) -> Self {
-        Self {
-            data_points: Arc::new(Mutex::new(VecDeque::with_capacity(max_points))),
-            max_points,
-        }
-    }
-
-    pub fn add_snapshot(&self, snapshot: Snapshot) {
-        let mut data = self.data_points.lock().unwrap();
-        if data.len() >= self.max_points {
-            data.pop_front();
-        }
-        data.push_back(snapshot);
-    }
-
-    pub fn get_recent_data(&self, duration_secs: u64) -> Vec<Snapshot> {
-        let data = self.data_points.lock().unwrap();
-        let cutoff_time = chrono::Utc::now().timestamp_millis() - (duration_secs as i64 * 1000);
-        
-        data.iter()
-            .filter(|snapshot| snapshot.ts >= cutoff_time)
-            .cloned()
-            .collect()
-    }
-
-    pub fn get_all_data(&self) -> Vec<Snapshot> {
-        let data = self.data_points.lock().unwrap();
-        data.iter().cloned().collect()
-    }
-}
-
 #[derive(Clone)]
 pub struct Supervisor { 
-    rx: Receiver<Snapshot>,
+    bus: SnapshotBus,
     historical_data: Arc<HistoricalData>,
+    root: collectors::fsroot::FsRoot,
 }
 
 impl Supervisor {
     pub async fn spawn(cfg: cfg::Config) -> anyhow::Result<Self> {
-        let (tx, rx) = unbounded();
+        let bus = SnapshotBus::new(bus::SUBSCRIBER_BUFFER);
         let interval = std::time::Duration::from_millis(cfg.refresh.interval_ms);
         
-        // Historical data storage (last 1 hour of data)
-        let historical_data = Arc::new(HistoricalData::new(3600)); // 3600 points = 1 hour at 1 second intervals
+        // Raw ticks for a few minutes, then 10 s and 1 min rollups
+        let historical_data = Arc::new(HistoricalData::new(&cfg.history));
         let historical_data_clone = historical_data.clone();
-        
-        // Store previous snapshot for calculating deltas
-        let previous_snapshot: Arc<Mutex<Option<Snapshot>>> = Arc::new(Mutex::new(None));
+        let publisher = bus.clone();
+        let root = cfg.paths.clone();
         
         tokio::spawn(async move {
-            // Create system object with all features enabled
-            let mut sys = sysinfo::System::new_all();
+            // Starts empty; each tick refreshes only what the due collectors read
+            let mut sys = sysinfo::System::new();
+            // smartctl queries this machine's drives, which only match the live root
+            let smart = (cfg!(target_os = "linux") && cfg.paths == collectors::fsroot::FsRoot::default())
+                .then(collectors::storage_health::SmartMonitor::system);
+            let mut registry = collectors::registry::CollectorRegistry::with_builtin(
+                cfg.collectors.clone(),
+                cfg.processes.clone(),
+                cfg.filesystems.clone(),
+                smart,
+            )
+            .with_root(cfg.paths.clone());
+            // Ticks keep their spacing however long collection takes; if it overruns a
+            // whole interval the missed ticks are skipped rather than bunched up
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
             loop {
-                // Refresh all system information
-                sys.refresh_all();
+                ticker.tick().await;
+                let started = std::time::Instant::now();
+                let now = chrono::Utc::now();
+                registry.refresh(&mut sys, now);
                 
-                // Use the associated function instead of method
-                let load_avg = sysinfo::System::load_average();
-                
-                // Get previous snapshot for calculating deltas
-                let prev = previous_snapshot.lock().unwrap().clone();
+                // The last published snapshot is the baseline for deltas
+                let prev = publisher.latest();
                 
                 // Debug: Print information about previous snapshot
                 if let Some(ref prev_snap) = prev {
@@ -88,15 +65,12 @@ impl Supervisor {
                     debug!("No previous snapshot");
                 }
                 
-                let snap = collectors::snapshot::Snapshot::from_sysinfo(
-                    chrono::Utc::now(), 
-                    &sys, 
-                    load_avg,
-                    prev.as_ref()
-                );
+                let mut snap = registry.collect(now, &sys, prev.as_deref());
+                snap.collection_ms = started.elapsed().as_secs_f64() * 1000.0;
+                debug!("Collection took {:.1} ms", snap.collection_ms);
                 
                 // Store in historical data
-                historical_data_clone.add_snapshot(snap.clone());
+                historical_data_clone.add_snapshot(&snap);
                 
                 // Debug: Print information about current snapshot
                 debug!("Current snapshot has {} network interfaces", snap.network.len());
@@ -105,27 +79,35 @@ impl Supervisor {
                               net.interface, net.rx_bytes, net.tx_bytes, net.rx_throughput, net.tx_throughput);
                 }
                 
-                // Store current snapshot for next iteration
-                *previous_snapshot.lock().unwrap() = Some(snap.clone());
-                
-                let _ = tx.send(snap);
-                tokio::time::sleep(interval).await;
+                publisher.publish(Arc::new(snap));
             }
         });
         
-        Ok(Self { rx, historical_data })
+        Ok(Self { bus, historical_data, root })
+    }
+    
+    // Every snapshot from now on, or a lag notice if the subscriber falls too far behind
+    pub fn subscribe(&self) -> SnapshotReceiver { 
+        self.bus.subscribe()
+    }
+    
+    // Most recent snapshot, without draining anything; None until the first tick completes
+    pub fn latest(&self) -> Option<Arc<Snapshot>> {
+        self.bus.latest()
     }
     
-    pub fn subscribe(&self) -> Receiver<Snapshot> { 
-        self.rx.clone() 
+    pub fn watch(&self) -> tokio::sync::watch::Receiver<Option<Arc<Snapshot>>> {
+        self.bus.watch()
     }
     
-    pub fn get_historical_data(&self, duration_secs: u64) -> Vec<Snapshot> {
+    // Resolution depends on how far back the range reaches
+    pub fn get_historical_data(&self, duration_secs: u64) -> HistoryRange {
         self.historical_data.get_recent_data(duration_secs)
     }
     
-    pub fn get_all_historical_data(&self) -> Vec<Snapshot> {
-        self.historical_data.get_all_data()
+    // On-demand detail for a single process; too expensive to gather for every process each tick
+    pub fn process_details(&self, pid: u32) -> Option<collectors::process::ProcessDetails> {
+        collectors::process::collect_process_details(&self.root, pid)
     }
 }
 
@@ -1,5 +1,5 @@
 use anyhow::Result; 
-use core_metrics::{Supervisor, collectors::{snapshot::Snapshot, net::NetworkInfo, disk::DiskInfo, process::ProcessInfo, sensors::SensorInfo, gpu::GpuInfo, containers::ContainerInfo}};
+use core_metrics::{Supervisor, collectors::{snapshot::Snapshot, net::{NetworkInfo, InterfaceKind}, disk::{DiskInfo, DiskIoInfo}, process::ProcessInfo, sensors::{SensorInfo, SensorUnit}, gpu::GpuInfo, containers::ContainerInfo, psi::{PressureInfo, ResourcePressure}, tcp::TcpStats}};
 use crossterm::{event, execute, terminal}; 
 use ratatui::{prelude::*, widgets::*}; 
 use std::{io, time::Duration};
@@ -11,6 +11,7 @@ struct TuiState {
     selected_table: usize, // 0 = Network, 1 = Disk, 2 = Process, 3 = Sensors, 4 = GPU, 5 = Containers
     table_selections: [usize; 6], // Selection index for each table
     table_states: [TableState; 6], // State for each table widget
+    show_veth: bool, // Container veth pairs are hidden unless toggled on
 }
 
 impl TuiState {
@@ -19,6 +20,7 @@ impl TuiState {
             selected_table: 0,
             table_selections: [0; 6],
             table_states: Default::default(), // This will initialize all TableStates to default
+            show_veth: false,
         }
     }
     
@@ -85,7 +87,127 @@ fn format_throughput(bytes_per_sec: f64) -> String {
     format!("{:.1} {}", size, UNITS[unit_index])
 }
 
-fn create_network_table(networks: &[NetworkInfo], _selected: bool, _selection_index: usize) -> Table<'_> {
+// One line per socket/NUMA node with average and busiest core, for multi-socket or
+// multi-node machines; None when there is only one group to show
+fn format_cpu_groups(s: &Snapshot) -> Option<String> {
+    let groups = s.topology.as_ref()?.cpu_groups();
+    if groups.len() < 2 {
+        return None;
+    }
+    let mut text = String::new();
+    for ((socket, node), cpus) in groups {
+        let usages: Vec<(usize, f32)> = cpus
+            .iter()
+            .filter_map(|&cpu| s.cpu_per_core.get(cpu).map(|u| (cpu, *u)))
+            .collect();
+        if usages.is_empty() {
+            continue;
+        }
+        let avg = usages.iter().map(|(_, u)| u).sum::<f32>() / usages.len() as f32;
+        let (max_cpu, max_usage) = usages.iter().copied().fold((0, f32::MIN), |a, b| if b.1 > a.1 { b } else { a });
+        let label = |v: Option<String>| v.unwrap_or_else(|| "?".to_string());
+        text.push_str(&format!("Socket {} Node {} ({} CPUs): avg {:.1}%, max CPU{} {:.1}%\n",
+            label(socket.map(|v| v.to_string())), label(node.map(|v| v.to_string())),
+            usages.len(), avg, max_cpu, max_usage));
+    }
+    Some(text)
+}
+
+// Host, OS, uptime and virtualization summary for the title bar
+fn format_system_header(s: &Snapshot) -> String {
+    let info = &s.system;
+    let uptime = info.uptime_secs;
+    let mut parts = vec![
+        info.host_name.clone(),
+        info.os_version.clone(),
+        format!("kernel {}", info.kernel_version),
+        info.arch.clone(),
+        format!("up {}d {}h {}m", uptime / 86400, uptime % 86400 / 3600, uptime % 3600 / 60),
+    ];
+    if let Some(virt) = &info.virtualization {
+        parts.push(format!("vm: {}", virt));
+    }
+    if let Some(container) = &info.container {
+        parts.push(format!("container: {}", container));
+    }
+    parts.push(format!("{} users", info.logged_in_users));
+    parts.push(format!("{} procs / {} threads", info.process_count, info.thread_count));
+    // zek's own collection cost, so it's obvious when monitoring gets expensive
+    parts.push(format!("zek {:.1}ms", s.collection_ms));
+    parts.retain(|p| !p.is_empty());
+    parts.join(" | ")
+}
+
+// Warning line for CPUs swamped by network softirqs
+fn format_net_saturation(s: &Snapshot) -> Option<String> {
+    let cpus = &s.interrupts.as_ref()?.net_saturated_cpus;
+    if cpus.is_empty() {
+        return None;
+    }
+    let cpus: Vec<String> = cpus.iter().map(|cpu| format!("CPU{}", cpu)).collect();
+    Some(format!("\nNET_RX/NET_TX softirq saturated: {}", cpus.join(", ")))
+}
+
+// Memory block title with cache/dirty sizes and reclaim activity when available
+fn format_memory_title(s: &Snapshot) -> String {
+    let mut title = String::from("Memory & Swap");
+    if let Some(m) = &s.memory {
+        title.push_str(&format!(" - cache {:.1} GiB, dirty {:.1} MiB",
+            (m.cached + m.buffers) as f64 / 1.0737e9, m.dirty as f64 / 1048576.0));
+    }
+    if let Some(v) = &s.vmstat {
+        title.push_str(&format!(", majflt {:.0}/s, direct scan {:.0}/s",
+            v.rates.pgmajfault, v.rates.pgscan_direct));
+    }
+    title
+}
+
+// Total CPU plus the /proc/stat breakdown when available
+fn format_cpu_label(s: &Snapshot) -> String {
+    match s.cpu_stats.as_ref().and_then(|c| c.total.breakdown.as_ref()) {
+        Some(b) => format!("{:.1}% (usr {:.1} sys {:.1} iowait {:.1} steal {:.1})",
+            s.cpu_total_pct, b.user + b.nice, b.system + b.irq + b.softirq, b.iowait, b.steal),
+        None => format!("{:.1}%", s.cpu_total_pct),
+    }
+}
+
+// One-line summary of the 10s "some" pressure averages
+fn format_pressure(pressure: Option<&PressureInfo>) -> String {
+    let Some(p) = pressure else {
+        return "Pressure: n/a".to_string();
+    };
+    let avg10 = |r: Option<&ResourcePressure>| match r {
+        Some(r) => format!("{:.1}%", r.some.avg10),
+        None => "-".to_string(),
+    };
+    format!("Pressure (avg10): cpu {} mem {} io {}", avg10(p.cpu.as_ref()), avg10(p.memory.as_ref()), avg10(p.io.as_ref()))
+}
+
+// Byte totals and rates over the given interfaces, then the TCP health counters
+fn format_net_summary(networks: &[&NetworkInfo], tcp: Option<&TcpStats>) -> String {
+    let total_rx: u64 = networks.iter().map(|n| n.rx_bytes).sum();
+    let total_tx: u64 = networks.iter().map(|n| n.tx_bytes).sum();
+    let total_rx_throughput: f64 = networks.iter().map(|n| n.rx_throughput).sum();
+    let total_tx_throughput: f64 = networks.iter().map(|n| n.tx_throughput).sum();
+    let mut summary = format!("RX {} ({}) TX {} ({})",
+        format_bytes(total_rx), format_throughput(total_rx_throughput),
+        format_bytes(total_tx), format_throughput(total_tx_throughput));
+    if let Some(tcp) = tcp {
+        summary.push_str(&format!("\nTCP: {} estab, retrans {:.1}/s ({:.2}%), resets {:.1}/s, listen drops {:.1}/s",
+            tcp.curr_estab, tcp.rates.retrans_segs, tcp.rates.retrans_percent,
+            tcp.rates.out_rsts + tcp.rates.estab_resets, tcp.rates.listen_drops));
+    }
+    summary
+}
+
+fn visible_networks(networks: &[NetworkInfo], show_veth: bool) -> Vec<&NetworkInfo> {
+    networks
+        .iter()
+        .filter(|net| show_veth || net.kind != InterfaceKind::Veth)
+        .collect()
+}
+
+fn create_network_table<'a>(networks: &[&'a NetworkInfo], _selected: bool, _selection_index: usize) -> Table<'a> {
     // Debug: Print network data
     debug!("Creating network table with {} interfaces", networks.len());
     for net in networks {
@@ -100,21 +222,34 @@ fn create_network_table(networks: &[NetworkInfo], _selected: bool, _selection_in
             .block(Block::default().title("Network Interfaces (0)").borders(Borders::ALL));
     }
     
-    let header = Row::new(vec!["Interface", "RX Bytes", "TX Bytes", "RX Throughput", "TX Throughput"])
+    let header = Row::new(vec!["Interface", "State", "RX Bytes", "TX Bytes", "RX Throughput", "TX Throughput", "Util", "Drops"])
         .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD));
     
     let rows: Vec<Row> = networks.iter().map(|net| {
+        let util = match net.utilization_percent {
+            Some(util) => format!("{:.1}%", util),
+            None => "-".to_string(),
+        };
         let row_data = vec![
             net.interface.clone(),
+            net.operstate.clone(),
             format_bytes(net.rx_bytes),
             format_bytes(net.tx_bytes),
             format_throughput(net.rx_throughput),
             format_throughput(net.tx_throughput),
+            util,
+            (net.rx_dropped + net.tx_dropped).to_string(),
         ];
         // Debug: Print row data
         debug!("Row data: {:?}", row_data);
         
-        let style = Style::default().fg(Color::White);
+        let style = if net.operstate == "down" {
+            Style::default().fg(Color::DarkGray)
+        } else if net.utilization_percent.unwrap_or(0.0) >= 80.0 {
+            Style::default().fg(Color::Red)
+        } else {
+            Style::default().fg(Color::White)
+        };
         Row::new(row_data).style(style)
     }).collect();
     
@@ -122,11 +257,14 @@ fn create_network_table(networks: &[NetworkInfo], _selected: bool, _selection_in
     let table = Table::new(
         rows,
         [
-            Constraint::Length(20),
+            Constraint::Length(16),
+            Constraint::Length(8),
             Constraint::Length(10),
             Constraint::Length(10),
             Constraint::Length(12),
             Constraint::Length(12),
+            Constraint::Length(7),
+            Constraint::Length(8),
         ]
     )
     .header(header)
@@ -140,7 +278,7 @@ fn create_network_table(networks: &[NetworkInfo], _selected: bool, _selection_in
     table
 }
 
-fn create_disk_table(disks: &[DiskInfo], _selected: bool, _selection_index: usize) -> Table<'_> {
+fn create_disk_table<'a>(disks: &'a [DiskInfo], disk_io: &[DiskIoInfo], _selected: bool, _selection_index: usize) -> Table<'a> {
     // Debug: Print disk data
     debug!("Creating disk table with {} disks", disks.len());
     for disk in disks {
@@ -154,24 +292,57 @@ fn create_disk_table(disks: &[DiskInfo], _selected: bool, _selection_index: usiz
             .block(Block::default().title("Disk Usage (0)").borders(Borders::ALL));
     }
     
-    let header = Row::new(vec!["Mount Point", "Total", "Used", "Available", "Usage"])
+    let header = Row::new(vec!["Mount Point", "Total", "Used", "Available", "Usage", "Inodes", "Read", "Write", "Util"])
         .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD));
     
     let rows: Vec<Row> = disks.iter().map(|disk| {
-        let usage_color = if disk.usage_percent < 70.0 {
+        // Running out of inodes fails writes just like running out of space
+        let usage = disk.usage_percent.max(disk.inode_usage_percent);
+        let smart_failed = disk.health.as_ref().and_then(|h| h.passed) == Some(false);
+        let usage_color = if smart_failed {
+            Color::Red
+        } else if usage < 70.0 {
             Color::Green
-        } else if disk.usage_percent < 85.0 {
+        } else if usage < 85.0 {
             Color::Yellow
         } else {
             Color::Red
         };
         
+        // I/O figures come from the block device backing this mount point, if known
+        let io = disk_io.iter().find(|io| io.mount_points.contains(&disk.mount_point));
+        let (read, write, util) = match io {
+            Some(io) => (
+                format_throughput(io.read_bytes_per_sec),
+                format_throughput(io.write_bytes_per_sec),
+                format!("{:.1}%", io.util_percent),
+            ),
+            None => ("-".to_string(), "-".to_string(), "-".to_string()),
+        };
+        
+        let mut mount_point = disk.mount_point.clone();
+        if disk.read_only {
+            mount_point.push_str(" (ro)");
+        }
+        if smart_failed {
+            mount_point.push_str(" (SMART failing)");
+        }
+        let inodes = if disk.inodes_total > 0 {
+            format!("{:.1}%", disk.inode_usage_percent)
+        } else {
+            "-".to_string()
+        };
+        
         let row_data = vec![
-            disk.mount_point.clone(),
+            mount_point,
             format_bytes(disk.total_space),
             format_bytes(disk.used_space),
             format_bytes(disk.available_space),
             format!("{:.1}%", disk.usage_percent),
+            inodes,
+            read,
+            write,
+            util,
         ];
         // Debug: Print row data
         debug!("Disk row data: {:?}", row_data);
@@ -183,11 +354,15 @@ fn create_disk_table(disks: &[DiskInfo], _selected: bool, _selection_index: usiz
     let table = Table::new(
         rows,
         [
-            Constraint::Percentage(30),
-            Constraint::Percentage(15),
-            Constraint::Percentage(15),
-            Constraint::Percentage(25),
-            Constraint::Percentage(15),
+            Constraint::Percentage(20),
+            Constraint::Percentage(10),
+            Constraint::Percentage(10),
+            Constraint::Percentage(10),
+            Constraint::Percentage(8),
+            Constraint::Percentage(8),
+            Constraint::Percentage(12),
+            Constraint::Percentage(12),
+            Constraint::Percentage(10),
         ]
     )
     .header(header)
@@ -267,7 +442,7 @@ fn create_containers_table(containers: &[ContainerInfo], _selected: bool, _selec
             .block(Block::default().title("Containers (0)").borders(Borders::ALL));
     }
     
-    let header = Row::new(vec!["Name", "State", "CPU%", "Memory", "Network"])
+    let header = Row::new(vec!["Name", "State", "CPU%", "Memory", "Disk R/W", "Throttled"])
         .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD));
     
     let rows: Vec<Row> = containers.iter().map(|container| {
@@ -282,8 +457,14 @@ fn create_containers_table(containers: &[ContainerInfo], _selected: bool, _selec
             container.name.clone(),
             format!("{:?}", container.state),
             format!("{:.1}", container.cpu_usage_percent),
-            format_bytes(container.memory_usage_bytes),
-            format!("RX: {} TX: {}", format_bytes(container.network_rx_bytes), format_bytes(container.network_tx_bytes)),
+            // A limit of 0 means the cgroup has no memory.max
+            if container.memory_limit_bytes > 0 {
+                format!("{} / {}", format_bytes(container.memory_usage_bytes), format_bytes(container.memory_limit_bytes))
+            } else {
+                format_bytes(container.memory_usage_bytes)
+            },
+            format!("{} / {}", format_bytes(container.disk_read_bytes), format_bytes(container.disk_write_bytes)),
+            format!("{}", container.cpu_nr_throttled),
         ];
         
         Row::new(row_data).style(Style::default().fg(state_color))
@@ -292,11 +473,12 @@ fn create_containers_table(containers: &[ContainerInfo], _selected: bool, _selec
     let table = Table::new(
         rows,
         [
-            Constraint::Percentage(25),
-            Constraint::Percentage(15),
+            Constraint::Percentage(20),
             Constraint::Percentage(10),
+            Constraint::Percentage(8),
             Constraint::Percentage(25),
             Constraint::Percentage(25),
+            Constraint::Percentage(12),
         ]
     )
     .header(header)
@@ -308,6 +490,34 @@ fn create_containers_table(containers: &[ContainerInfo], _selected: bool, _selec
     table
 }
 
+// Colour against the chip's own limits; readings without limits aren't judged
+fn sensor_color(sensor: &SensorInfo) -> Color {
+    match sensor.unit {
+        // A fan alarms when it slows below fanN_min; fanN_max is only a target speed
+        SensorUnit::Rpm => match sensor.min {
+            Some(min) if sensor.value < min => Color::Red,
+            Some(_) => Color::Green,
+            None => Color::White,
+        },
+        // Voltages alarm outside their inN_min..inN_max window
+        SensorUnit::Volts => match (sensor.min, sensor.max) {
+            (Some(min), _) if sensor.value < min => Color::Red,
+            (_, Some(max)) if sensor.value > max => Color::Red,
+            (None, None) => Color::White,
+            _ => Color::Green,
+        },
+        _ => match (sensor.critical, sensor.max) {
+            (Some(crit), _) if sensor.value >= crit => Color::Red,
+            (Some(_), Some(max)) if sensor.value >= max => Color::Yellow,
+            (Some(crit), None) if sensor.value >= crit * 0.9 => Color::Yellow,
+            (None, Some(max)) if sensor.value >= max => Color::Red,
+            (None, Some(max)) if sensor.value >= max * 0.9 => Color::Yellow,
+            (None, None) => Color::White,
+            _ => Color::Green,
+        },
+    }
+}
+
 fn create_sensors_table(sensors: &[SensorInfo], _selected: bool, _selection_index: usize) -> Table<'_> {
     if sensors.is_empty() {
         let rows = vec![Row::new(vec!["No sensor data available"])];
@@ -315,33 +525,33 @@ fn create_sensors_table(sensors: &[SensorInfo], _selected: bool, _selection_inde
             .block(Block::default().title("System Sensors (0)").borders(Borders::ALL));
     }
     
-    let header = Row::new(vec!["Component", "Label", "Temperature"])
+    let header = Row::new(vec!["Chip", "Label", "Value", "Max", "Critical"])
         .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD));
     
     let rows: Vec<Row> = sensors.iter().map(|sensor| {
-        let temp_color = if sensor.temperature < 50.0 {
-            Color::Green
-        } else if sensor.temperature < 70.0 {
-            Color::Yellow
-        } else {
-            Color::Red
-        };
+        let sensor_color = sensor_color(sensor);
         
+        let unit = sensor.unit.symbol();
+        let threshold = |t: Option<f32>| t.map_or("-".to_string(), |t| format!("{:.1}{}", t, unit));
         let row_data = vec![
             sensor.component.clone(),
             sensor.label.clone(),
-            format!("{:.1}°C", sensor.temperature),
+            format!("{:.1}{}", sensor.value, unit),
+            threshold(sensor.max),
+            threshold(sensor.critical),
         ];
         
-        Row::new(row_data).style(Style::default().fg(temp_color))
+        Row::new(row_data).style(Style::default().fg(sensor_color))
     }).collect();
     
     let table = Table::new(
         rows,
         [
+            Constraint::Percentage(20),
             Constraint::Percentage(30),
-            Constraint::Percentage(40),
-            Constraint::Percentage(30),
+            Constraint::Percentage(17),
+            Constraint::Percentage(16),
+            Constraint::Percentage(17),
         ]
     )
     .header(header)
@@ -409,13 +619,10 @@ pub async fn run_tui(sup: Supervisor) -> Result<()> {
     let backend = CrosstermBackend::new(out);
     let mut term = Terminal::new(backend)?;
     
-    let mut last: Option<Snapshot> = None;
     let mut state = TuiState::new();
     
     loop {
-        while let Ok(s) = sup.subscribe().try_recv() {
-            last = Some(s);
-        }
+        let last = sup.latest();
         
         term.draw(|f| {
             // Get terminal size to adjust layout
@@ -474,7 +681,11 @@ pub async fn run_tui(sup: Supervisor) -> Result<()> {
                 .split(f.area());
             
             // Title
-            let title = Paragraph::new("ZEK SYSTEM MONITOR")
+            let title_text = match &last {
+                Some(s) => format!("ZEK SYSTEM MONITOR\n{}", format_system_header(s)),
+                None => "ZEK SYSTEM MONITOR".to_string(),
+            };
+            let title = Paragraph::new(title_text)
                 .alignment(Alignment::Center)
                 .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD));
             f.render_widget(title, chunks[0]);
@@ -499,7 +710,7 @@ pub async fn run_tui(sup: Supervisor) -> Result<()> {
                         .block(Block::default().title("CPU Usage").borders(Borders::ALL))
                         .gauge_style(Style::default().fg(cpu_color))
                         .percent(s.cpu_total_pct as u16)
-                        .label(format!("{:.1}%", s.cpu_total_pct));
+                        .label(format_cpu_label(s));
                     f.render_widget(cpu_gauge, cpu_mem_chunks[0]);
                     
                     // Memory Gauge (including swap)
@@ -518,7 +729,7 @@ pub async fn run_tui(sup: Supervisor) -> Result<()> {
                         swap_used/1.0737e9, swap_total/1.0737e9);
                     
                     let mem_gauge = Gauge::default()
-                        .block(Block::default().title("Memory & Swap").borders(Borders::ALL))
+                        .block(Block::default().title(format_memory_title(s)).borders(Borders::ALL))
                         .gauge_style(Style::default().fg(mem_color))
                         .ratio(mem_ratio)
                         .label(mem_label);
@@ -531,37 +742,36 @@ pub async fn run_tui(sup: Supervisor) -> Result<()> {
                         .split(chunks[2]);
                     
                     // Load Average
-                    let load_text = format!("Load: {:.2} {:.2} {:.2}", s.load1, s.load5, s.load15);
+                    let load_text = format!("Load: {:.2} {:.2} {:.2}\n{}", s.load1, s.load5, s.load15, format_pressure(s.pressure.as_ref()));
                     let load_widget = Paragraph::new(load_text)
                         .block(Block::default().title("System Load").borders(Borders::ALL))
                         .alignment(Alignment::Left);
                     f.render_widget(load_widget, load_net_chunks[0]);
                     
-                    // Network Summary
-                    let total_rx: u64 = s.network.iter().map(|n| n.rx_bytes).sum();
-                    let total_tx: u64 = s.network.iter().map(|n| n.tx_bytes).sum();
-                    let total_rx_throughput: f64 = s.network.iter().map(|n| n.rx_throughput).sum();
-                    let total_tx_throughput: f64 = s.network.iter().map(|n| n.tx_throughput).sum();
-                    let net_summary = format!("RX {} ({})\nTX {} ({})", 
-                        format_bytes(total_rx), format_throughput(total_rx_throughput),
-                        format_bytes(total_tx), format_throughput(total_tx_throughput));
+                    // Network Summary, over the same interfaces as the table so veth traffic isn't counted twice
+                    let net_summary = format_net_summary(&visible_networks(&s.network, state.show_veth), s.tcp.as_ref());
                     let net_widget = Paragraph::new(net_summary)
                         .block(Block::default().title("Network").borders(Borders::ALL))
                         .alignment(Alignment::Left);
                     f.render_widget(net_widget, load_net_chunks[1]);
                     
                     // Per-core CPU Usage
-                    let mut cpu_text = String::new();
-                    for (i, cpu_usage) in s.cpu_per_core.iter().enumerate() {
-                        // Show every 4th core to avoid overcrowding
-                        if i % 4 == 0 || i == s.cpu_per_core.len() - 1 {
-                            cpu_text.push_str(&format!("CPU{}: {:.1}%  ", i, cpu_usage));
-                        }
-                        // Add newline every few CPUs
-                        if (i + 1) % 4 == 0 {
-                            cpu_text.push('\n');
+                    let mut cpu_text = format_cpu_groups(s).unwrap_or_default();
+                    if cpu_text.is_empty() {
+                        for (i, cpu_usage) in s.cpu_per_core.iter().enumerate() {
+                            // Show every 4th core to avoid overcrowding
+                            if i % 4 == 0 || i == s.cpu_per_core.len() - 1 {
+                                cpu_text.push_str(&format!("CPU{}: {:.1}%  ", i, cpu_usage));
+                            }
+                            // Add newline every few CPUs
+                            if (i + 1) % 4 == 0 {
+                                cpu_text.push('\n');
+                            }
                         }
                     }
+                    if let Some(warning) = format_net_saturation(s) {
+                        cpu_text.push_str(&warning);
+                    }
                     if cpu_text.is_empty() {
                         cpu_text = "No CPU data".to_string();
                     }
@@ -577,11 +787,11 @@ pub async fn run_tui(sup: Supervisor) -> Result<()> {
                         .split(chunks[4]);
                     
                     // Network Data
-                    let network_table = create_network_table(&s.network, state.selected_table == 0, state.table_selections[0]);
+                    let network_table = create_network_table(&visible_networks(&s.network, state.show_veth), state.selected_table == 0, state.table_selections[0]);
                     f.render_stateful_widget(network_table, net_disk_chunks[0], &mut state.table_states[0]);
                     
                     // Disk Data
-                    let disk_table = create_disk_table(&s.disks, state.selected_table == 1, state.table_selections[1]);
+                    let disk_table = create_disk_table(&s.disks, &s.disk_io, state.selected_table == 1, state.table_selections[1]);
                     f.render_stateful_widget(disk_table, net_disk_chunks[1], &mut state.table_states[1]);
                     
                     // Combined Sensors and GPU
@@ -626,7 +836,7 @@ pub async fn run_tui(sup: Supervisor) -> Result<()> {
                         .block(Block::default().title("CPU Usage").borders(Borders::ALL))
                         .gauge_style(Style::default().fg(cpu_color))
                         .percent(s.cpu_total_pct as u16)
-                        .label(format!("{:.1}%", s.cpu_total_pct));
+                        .label(format_cpu_label(s));
                     f.render_widget(cpu_gauge, cpu_mem_chunks[0]);
                     
                     // Memory Gauge (including swap)
@@ -645,7 +855,7 @@ pub async fn run_tui(sup: Supervisor) -> Result<()> {
                         swap_used/1.0737e9, swap_total/1.0737e9);
                     
                     let mem_gauge = Gauge::default()
-                        .block(Block::default().title("Memory & Swap").borders(Borders::ALL))
+                        .block(Block::default().title(format_memory_title(s)).borders(Borders::ALL))
                         .gauge_style(Style::default().fg(mem_color))
                         .ratio(mem_ratio)
                         .label(mem_label);
@@ -658,37 +868,36 @@ pub async fn run_tui(sup: Supervisor) -> Result<()> {
                         .split(chunks[2]);
                     
                     // Load Average
-                    let load_text = format!("Load Average: {:.2} {:.2} {:.2}", s.load1, s.load5, s.load15);
+                    let load_text = format!("Load Average: {:.2} {:.2} {:.2}\n{}", s.load1, s.load5, s.load15, format_pressure(s.pressure.as_ref()));
                     let load_widget = Paragraph::new(load_text)
                         .block(Block::default().title("System Load").borders(Borders::ALL))
                         .alignment(Alignment::Left);
                     f.render_widget(load_widget, load_net_chunks[0]);
                     
-                    // Network Summary
-                    let total_rx: u64 = s.network.iter().map(|n| n.rx_bytes).sum();
-                    let total_tx: u64 = s.network.iter().map(|n| n.tx_bytes).sum();
-                    let total_rx_throughput: f64 = s.network.iter().map(|n| n.rx_throughput).sum();
-                    let total_tx_throughput: f64 = s.network.iter().map(|n| n.tx_throughput).sum();
-                    let net_summary = format!("Network: RX {} ({}) TX {} ({})", 
-                        format_bytes(total_rx), format_throughput(total_rx_throughput),
-                        format_bytes(total_tx), format_throughput(total_tx_throughput));
+                    // Network Summary, over the same interfaces as the table so veth traffic isn't counted twice
+                    let net_summary = format_net_summary(&visible_networks(&s.network, state.show_veth), s.tcp.as_ref());
                     let net_widget = Paragraph::new(net_summary)
                         .block(Block::default().title("Network Summary").borders(Borders::ALL))
                         .alignment(Alignment::Left);
                     f.render_widget(net_widget, load_net_chunks[1]);
                     
                     // Per-core CPU Usage
-                    let mut cpu_text = String::new();
-                    for (i, cpu_usage) in s.cpu_per_core.iter().enumerate() {
-                        // Show every 4th core to avoid overcrowding
-                        if i % 4 == 0 || i == s.cpu_per_core.len() - 1 {
-                            cpu_text.push_str(&format!("CPU{}: {:.1}%  ", i, cpu_usage));
-                        }
-                        // Add newline every few CPUs
-                        if (i + 1) % 8 == 0 {
-                            cpu_text.push('\n');
+                    let mut cpu_text = format_cpu_groups(s).unwrap_or_default();
+                    if cpu_text.is_empty() {
+                        for (i, cpu_usage) in s.cpu_per_core.iter().enumerate() {
+                            // Show every 4th core to avoid overcrowding
+                            if i % 4 == 0 || i == s.cpu_per_core.len() - 1 {
+                                cpu_text.push_str(&format!("CPU{}: {:.1}%  ", i, cpu_usage));
+                            }
+                            // Add newline every few CPUs
+                            if (i + 1) % 8 == 0 {
+                                cpu_text.push('\n');
+                            }
                         }
                     }
+                    if let Some(warning) = format_net_saturation(s) {
+                        cpu_text.push_str(&warning);
+                    }
                     if cpu_text.is_empty() {
                         cpu_text = "No CPU data available".to_string();
                     }
@@ -698,11 +907,11 @@ pub async fn run_tui(sup: Supervisor) -> Result<()> {
                     f.render_widget(cpu_widget, chunks[3]);
                     
                     // Network Data - using proper table widget
-                    let network_table = create_network_table(&s.network, state.selected_table == 0, state.table_selections[0]);
+                    let network_table = create_network_table(&visible_networks(&s.network, state.show_veth), state.selected_table == 0, state.table_selections[0]);
                     f.render_stateful_widget(network_table, chunks[4], &mut state.table_states[0]);
                     
                     // Disk Data - using proper table widget
-                    let disk_table = create_disk_table(&s.disks, state.selected_table == 1, state.table_selections[1]);
+                    let disk_table = create_disk_table(&s.disks, &s.disk_io, state.selected_table == 1, state.table_selections[1]);
                     f.render_stateful_widget(disk_table, chunks[5], &mut state.table_states[1]);
                     
                     // Sensors Data
@@ -730,7 +939,7 @@ pub async fn run_tui(sup: Supervisor) -> Result<()> {
             
             // Footer with current selection info
             let table_names = ["Network", "Disk", "Sensors", "GPU", "Containers", "Processes"];
-            let footer_text = format!("Press 'q' or ESC to quit | Tab: Switch tables | ↑/↓: Navigate | Selected: {}", 
+            let footer_text = format!("Press 'q' or ESC to quit | Tab: Switch tables | ↑/↓: Navigate | v: Show/hide veth | Selected: {}", 
                                      table_names[state.selected_table]);
             let footer = Paragraph::new(footer_text)
                 .alignment(Alignment::Center)
@@ -744,11 +953,12 @@ pub async fn run_tui(sup: Supervisor) -> Result<()> {
                 match k.code {
                     Char('q') | Esc => break,
                     Tab => state.next_table(),
+                    Char('v') => state.show_veth = !state.show_veth,
                     BackTab => state.previous_table(),
                     Down => {
                         if let Some(s) = &last {
                             let max_items = match state.selected_table {
-                                0 => s.network.len(),
+                                0 => visible_networks(&s.network, state.show_veth).len(),
                                 1 => s.disks.len(),
                                 2 => s.sensors.len(),
                                 3 => s.gpus.len(),
@@ -762,7 +972,7 @@ pub async fn run_tui(sup: Supervisor) -> Result<()> {
                     Up => {
                         if let Some(s) = &last {
                             let max_items = match state.selected_table {
-                                0 => s.network.len(),
+                                0 => visible_networks(&s.network, state.show_veth).len(),
                                 1 => s.disks.len(),
                                 2 => s.sensors.len(),
                                 3 => s.gpus.len(),
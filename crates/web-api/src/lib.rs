@@ -1,6 +1,6 @@
 use axum::{
     routing::{get, post},
-    Json, Router, extract::State,
+    Json, Router, extract::{Query, State},
     http::StatusCode,
     response::IntoResponse
 };
@@ -40,6 +40,7 @@ pub struct ExportRequest {
 pub async fn serve_api(bind:String,sup:Supervisor)->anyhow::Result<()>{
     let app = Router::new()
         .route("/api/snapshot", get(snapshot))
+        .route("/api/pressure", get(pressure))
         .route("/api/history", get(get_historical_data))
         .route("/api/alerts", get(get_alerts).post(create_alert))
         .route("/api/export", post(export_data))
@@ -53,27 +54,35 @@ pub async fn serve_api(bind:String,sup:Supervisor)->anyhow::Result<()>{
 }
 
 async fn snapshot(State(app):State<App>)->Json<serde_json::Value>{
-    let mut last=None; 
-    while let Ok(s)=app.sup.subscribe().try_recv(){ 
-        last=Some(s); 
-    }
-    Json(match last{
-        Some(s)=>serde_json::json!(s),
+    Json(match app.sup.latest(){
+        Some(s)=>serde_json::json!(&*s),
         None=>serde_json::json!({"status":"no-data-yet"})
     })
 }
 
-async fn get_historical_data(State(_app): State<App>) -> impl IntoResponse {
-    // Return mock historical data for now
-    let data = vec![
-        HistoricalDataPoint {
-            timestamp: chrono::Utc::now().timestamp_millis(),
-            metric: "cpu_usage".to_string(),
-            value: 45.5,
-        }
-    ];
-    
-    (StatusCode::OK, Json(data))
+async fn pressure(State(app):State<App>)->Json<serde_json::Value>{
+    Json(match app.sup.latest(){
+        Some(s)=>match &s.pressure{
+            Some(p)=>serde_json::json!({"ts":s.ts,"load1":s.load1,"load5":s.load5,"load15":s.load15,"pressure":p}),
+            None=>serde_json::json!({"status":"psi-unavailable"})
+        },
+        None=>serde_json::json!({"status":"no-data-yet"})
+    })
+}
+
+#[derive(Deserialize, Debug)]
+struct HistoryQuery {
+    #[serde(default = "default_history_secs")]
+    duration_secs: u64,
+}
+
+fn default_history_secs() -> u64 {
+    3600
+}
+
+// The range picks its own tier: raw samples for short windows, rollups for longer ones
+async fn get_historical_data(State(app): State<App>, Query(query): Query<HistoryQuery>) -> impl IntoResponse {
+    (StatusCode::OK, Json(app.sup.get_historical_data(query.duration_secs)))
 }
 
 async fn get_alerts(State(_app): State<App>) -> impl IntoResponse {
@@ -0,0 +1,98 @@
+#!/usr/bin/env bash
+# Capture the /proc, /sys and /run files zek's collectors read into a tarball, so odd hosts
+# can be replayed in tests: unpack it under crates/core-metrics/tests/fixtures/hosts/<name>/
+# and point FsRoot::capture() (or [paths] in zek.toml) at it.
+#
+# Usage: scripts/capture_host.sh [output.tar.gz]
+set -euo pipefail
+
+out="${1:-zek-capture-$(hostname)-$(date +%Y%m%d%H%M%S).tar.gz}"
+staging="$(mktemp -d)"
+trap 'rm -rf "$staging"' EXIT
+
+# procfs and sysfs report a size of 0 for most files, so tar can't read them directly;
+# copy the contents out with cat first
+grab() {
+  local src="$1"
+  [ -f "$src" ] && [ -r "$src" ] || return 0
+  mkdir -p "$staging$(dirname "$src")"
+  cat "$src" > "$staging$src" 2>/dev/null || rm -f "$staging$src"
+}
+
+# Every file up to $2 levels below a directory, following the symlinks sysfs class
+# directories are made of. Directories at the depth limit are kept empty, since some
+# collectors only check that they exist (e.g. class/net/*/bridge)
+grab_tree() {
+  local dir="$1" depth="$2"
+  [ -d "$dir" ] || return 0
+  while IFS= read -r path; do
+    if [ -d "$path" ]; then
+      mkdir -p "$staging$path"
+    else
+      grab "$path"
+    fi
+  done < <(find -L "$dir" -mindepth 1 -maxdepth "$depth" 2>/dev/null)
+}
+
+# Symlinks whose target name is what gets read (/sys/dev/block/8:1 -> ../../block/sda/sda1):
+# recreate the link along with an empty directory at its target
+grab_link() {
+  local link="$1"
+  [ -L "$link" ] || return 0
+  mkdir -p "$staging$(readlink -f "$link")" "$staging$(dirname "$link")"
+  ln -sfn "$(readlink "$link")" "$staging$link"
+}
+
+for f in stat meminfo vmstat interrupts softirqs loadavg cpuinfo diskstats mdstat \
+         pressure/cpu pressure/memory pressure/io \
+         net/dev net/snmp net/netstat net/sockstat \
+         net/tcp net/tcp6 net/udp net/udp6 net/raw net/raw6 net/unix \
+         sys/net/ipv4/tcp_mem sys/kernel/osrelease 1/cgroup self/mountinfo; do
+  grab "/proc/$f"
+done
+# /proc/1/environ is left out on purpose: it can hold secrets
+grab_tree /proc/spl/kstat/zfs 2
+
+for cpu in /sys/devices/system/cpu/cpu[0-9]*; do
+  grab_tree "$cpu/topology" 1
+  grab "$cpu/cpufreq/scaling_cur_freq"
+  grab_tree "$cpu/thermal_throttle" 1
+done
+for node in /sys/devices/system/node/node[0-9]*; do
+  grab "$node/cpulist"
+  grab "$node/meminfo"
+  grab "$node/numastat"
+done
+
+grab_tree /sys/class/net 2
+grab_tree /sys/class/hwmon 3
+grab_tree /sys/class/power_supply 2
+grab_tree /sys/class/powercap 2
+# Deep enough for kubepods.slice/<qos>.slice/<pod>.slice/<container>.scope
+grab_tree /sys/fs/cgroup 7
+for dev in /sys/dev/block/*; do
+  grab_link "$dev"
+done
+for block in /sys/class/block/*; do
+  grab_link "$block"
+  grab "$(readlink -f "$block")/partition"
+done
+for dm in /sys/block/dm-*; do
+  grab_tree "$dm/dm" 1
+  grab_tree "$dm/slaves" 1
+done
+# Detached loop devices have no backing_file; without it replay would hide the attached ones too
+for l in /sys/block/loop*; do
+  grab "$l/loop/backing_file"
+done
+for f in sys_vendor product_name bios_vendor; do
+  grab "/sys/class/dmi/id/$f"
+done
+grab /sys/hypervisor/type
+
+grab /run/systemd/container
+grab /run/.containerenv
+# utmp and /run/systemd/sessions are left out on purpose: they say who is logged in from where
+
+tar -C "$staging" -czf "$out" .
+echo "Wrote $out"